@@ -2,9 +2,38 @@ use std::collections::HashSet;
 
 use anyhow::{Context, Ok};
 use omegle_rs::{
-    omegle::Omegle, status::OmegleStatus, types::chat_event::ChatEvent, types::lang::LangCode,
+    chat_session::ChatSession,
+    event_handler::{async_trait, EventHandler},
+    omegle::Omegle,
+    status::OmegleStatus,
+    types::lang::LangCode,
 };
 
+struct Printer;
+
+#[async_trait]
+impl EventHandler for Printer {
+    async fn on_waiting(&mut self, _session: &ChatSession) {
+        println!("Waiting")
+    }
+
+    async fn on_connected(&mut self, _session: &ChatSession) {
+        println!("Connected")
+    }
+
+    async fn on_typing(&mut self, _session: &ChatSession) {
+        print!("Typing\r")
+    }
+
+    async fn on_message(&mut self, _session: &ChatSession, message: String) {
+        println!("Got message: {message}")
+    }
+
+    async fn on_disconnected(&mut self, _session: &ChatSession) {
+        println!("Disconnected")
+    }
+}
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     let status = OmegleStatus::get_omegle_status()
@@ -16,25 +45,10 @@ async fn main() -> anyhow::Result<()> {
         .new_chat()
         .await
         .context("Could not start a new chat")?;
-    'mainloop: loop {
-        let events = session
-            .get_events()
-            .await
-            .context("Failed getting events")?;
-        for event in events {
-            match event {
-                ChatEvent::Waiting => println!("Waiting"),
-                ChatEvent::Connected => println!("Connected"),
-                ChatEvent::StartedTyping => print!("Typing\r"),
-                ChatEvent::Message(msg) => println!("Got message: {msg}"),
-                ChatEvent::Disconnected => {
-                    println!("Disconnected");
-                    break 'mainloop;
-                }
-                _ => {}
-            }
-        }
-    }
+    session
+        .run(&mut Printer)
+        .await
+        .context("Failed getting events")?;
 
     Ok(())
 }
@@ -0,0 +1,361 @@
+//! Reacting to omegle bans
+//!
+//! Omegle bans clients it flags by sending a [`ChatEvent::Banned`]. Every
+//! [`ChatSession`](crate::chat_session::ChatSession) started by an
+//! [`Omegle`](crate::omegle::Omegle) reports it, after which new chats fail with
+//! [`OmegleLibError::Banned`](crate::types::error::OmegleLibError::Banned) until the
+//! cooldown of its [`BanPolicy`] is over. There is no cooldown by default, so new
+//! chats are started right away like before bans were tracked.
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use tokio::time::Instant;
+
+#[cfg(doc)]
+use crate::types::chat_event::ChatEvent;
+
+/// How an [`Omegle`](crate::omegle::Omegle) reacts to being banned
+///
+/// Bans are not enforced by default: they are recorded, but the cooldown is zero so
+/// [`Omegle::new_chat`](crate::omegle::Omegle::new_chat) never fails with
+/// [`OmegleLibError::Banned`](crate::types::error::OmegleLibError::Banned) until one is set
+///
+/// # Examples
+/// Wait 10 minutes and come back with a new random ID
+/// ```rust
+/// use omegle_rs::ban::BanPolicy;
+/// use omegle_rs::omegle::Omegle;
+/// use std::time::Duration;
+///
+/// fn handle_bans(omegle: &mut Omegle) {
+///     omegle.update_ban_policy(
+///         BanPolicy::new()
+///             .cooldown(Duration::from_secs(600))
+///             .rotate_rand_id(true),
+///     )
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BanPolicy {
+    cooldown: Duration,
+    rotate_rand_id: bool,
+    #[cfg(feature = "proxy")]
+    switch_proxy: bool,
+}
+
+impl Default for BanPolicy {
+    fn default() -> Self {
+        Self {
+            cooldown: Duration::ZERO,
+            rotate_rand_id: false,
+            #[cfg(feature = "proxy")]
+            switch_proxy: false,
+        }
+    }
+}
+
+impl BanPolicy {
+    /// Creates a new [`BanPolicy`] that doesn't wait or change anything after a ban,
+    /// bans are only recorded
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets how long after a ban new chats fail
+    pub fn cooldown(mut self, cooldown: Duration) -> Self {
+        self.cooldown = cooldown;
+        self
+    }
+
+    /// Sets whether a new [`RandID`](crate::types::rand_id::RandID) is used for the
+    /// chats started after a ban
+    pub fn rotate_rand_id(mut self, rotate_rand_id: bool) -> Self {
+        self.rotate_rand_id = rotate_rand_id;
+        self
+    }
+
+    /// Sets whether new chats keep being started through the other proxies of the
+    /// [proxy pool](crate::omegle::Omegle::update_proxy_pool) during the cooldown,
+    /// requires the `proxy` feature
+    ///
+    /// Either way the banned proxy is taken out of the pool and put back once the
+    /// cooldown is over
+    #[cfg(feature = "proxy")]
+    pub fn switch_proxy(mut self, switch_proxy: bool) -> Self {
+        self.switch_proxy = switch_proxy;
+        self
+    }
+
+    /// Gets how long after a ban new chats fail
+    pub fn get_cooldown(&self) -> Duration {
+        self.cooldown
+    }
+
+    /// Gets whether a new random ID is used for the chats started after a ban
+    pub fn is_rotate_rand_id(&self) -> bool {
+        self.rotate_rand_id
+    }
+
+    /// Gets whether new chats are started through the other proxies during the cooldown
+    #[cfg(feature = "proxy")]
+    pub fn is_switch_proxy(&self) -> bool {
+        self.switch_proxy
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Ban {
+    at: Instant,
+    /// Whether the ban was already acted on by starting a chat
+    handled: bool,
+}
+
+/// The last ban of an [`Omegle`](crate::omegle::Omegle), shared with its chats
+#[derive(Debug, Default)]
+pub(crate) struct BanRecord {
+    ban: Mutex<Option<Ban>>,
+}
+
+/// What a [`BanRecord`] says about starting a new chat
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum BanStatus {
+    /// There was no ban
+    Clear,
+    /// The cooldown of the last ban is over, the ban was forgotten
+    Expired { handled: bool },
+    /// Still cooling down, `handled` is whether a chat was started since the ban
+    CoolingDown { remaining: Duration, handled: bool },
+}
+
+impl BanRecord {
+    /// Records a ban that just happened
+    pub(crate) fn record(&self, at: Instant) {
+        *self.ban.lock().expect("lock is not poisoned") = Some(Ban { at, handled: false })
+    }
+
+    /// Gets when the last ban happened, if it is still remembered
+    pub(crate) fn get_banned_at(&self) -> Option<Instant> {
+        self.ban
+            .lock()
+            .expect("lock is not poisoned")
+            .map(|ban| ban.at)
+    }
+
+    /// Gets how long is left of the cooldown of the last ban, if it isn't over
+    pub(crate) fn get_remaining(&self, cooldown: Duration, now: Instant) -> Option<Duration> {
+        let at = self.get_banned_at()?;
+        Some(cooldown.saturating_sub(now.saturating_duration_since(at)))
+            .filter(|left| !left.is_zero())
+    }
+
+    /// Forgets the last ban
+    pub(crate) fn clear(&self) {
+        *self.ban.lock().expect("lock is not poisoned") = None
+    }
+
+    /// Checks the last ban against `cooldown` before starting a chat, marking it handled
+    pub(crate) fn check(&self, cooldown: Duration, now: Instant) -> BanStatus {
+        let mut ban = self.ban.lock().expect("lock is not poisoned");
+        let Some(Ban { at, handled }) = *ban else {
+            return BanStatus::Clear;
+        };
+        let remaining = cooldown.saturating_sub(now.saturating_duration_since(at));
+        if remaining.is_zero() {
+            *ban = None;
+            BanStatus::Expired { handled }
+        } else {
+            *ban = Some(Ban { at, handled: true });
+            BanStatus::CoolingDown { remaining, handled }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::sync::Arc;
+
+    use crate::omegle::{tests::test_status, Omegle};
+    use crate::transport::MemoryTransport;
+    use crate::types::chat_event::ChatEvent;
+    use crate::types::error::OmegleLibError;
+    use crate::types::lang::LangCode;
+
+    use super::*;
+
+    const START: &str =
+        r#"{"clientID": "central1:aaaaaaaaaaaaaaaaaaaaaaaaaaaaaa", "events": [["waiting"]]}"#;
+
+    fn push_chat(transport: &MemoryTransport) {
+        transport.push_response("/check", "checkcode");
+        transport.push_response("/start", START);
+    }
+
+    /// Starts a chat with `omegle` and gets it banned
+    async fn get_banned(omegle: &Omegle, transport: &MemoryTransport) {
+        push_chat(transport);
+        transport.push_response("/events", r#"[["antinudeBanned"]]"#);
+        let session = omegle.new_chat().await.unwrap();
+        session.get_events().await.unwrap();
+        assert_eq!(
+            session.get_events().await.unwrap().into_vec(),
+            vec![ChatEvent::Banned]
+        );
+    }
+
+    fn rand_id_sent(transport: &MemoryTransport) -> String {
+        let start = transport
+            .requests()
+            .into_iter()
+            .rfind(|request| request.url.contains("/start"))
+            .unwrap();
+        let url = reqwest::Url::parse(&start.url).unwrap();
+        url.query_pairs()
+            .find(|(key, _)| key == "randid")
+            .map(|(_, value)| value.into_owned())
+            .unwrap()
+    }
+
+    #[test]
+    fn cools_down_from_the_last_ban() {
+        let record = BanRecord::default();
+        let start = Instant::now();
+        let cooldown = Duration::from_secs(60);
+        assert_eq!(record.check(cooldown, start), BanStatus::Clear);
+
+        record.record(start);
+        assert_eq!(
+            record.check(cooldown, start + Duration::from_secs(20)),
+            BanStatus::CoolingDown {
+                remaining: Duration::from_secs(40),
+                handled: false
+            }
+        );
+        assert_eq!(
+            record.check(cooldown, start + Duration::from_secs(60)),
+            BanStatus::Expired { handled: true }
+        );
+        assert_eq!(record.get_banned_at(), None)
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn new_chat_fails_until_cooldown_is_over() {
+        let transport = Arc::new(MemoryTransport::new());
+        let mut omegle = Omegle::with_transport(
+            test_status(),
+            HashSet::new(),
+            LangCode::English,
+            transport.clone(),
+        );
+        omegle.update_ban_policy(BanPolicy::new().cooldown(Duration::from_secs(60)));
+        let rand_id = omegle.get_rand_id();
+
+        get_banned(&omegle, &transport).await;
+        tokio::time::advance(Duration::from_secs(20)).await;
+
+        assert!(matches!(
+            omegle.new_chat().await,
+            Err(OmegleLibError::Banned(remaining)) if remaining == Duration::from_secs(40)
+        ));
+        assert_eq!(omegle.get_ban_remaining(), Some(Duration::from_secs(40)));
+
+        tokio::time::advance(Duration::from_secs(40)).await;
+        push_chat(&transport);
+        omegle.new_chat().await.unwrap();
+        assert_eq!(omegle.get_banned_at(), None);
+        assert_eq!(rand_id_sent(&transport), String::from(rand_id))
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn default_policy_records_bans_without_enforcing_them() {
+        let transport = Arc::new(MemoryTransport::new());
+        let omegle = Omegle::with_transport(
+            test_status(),
+            HashSet::new(),
+            LangCode::English,
+            transport.clone(),
+        );
+        let rand_id = omegle.get_rand_id();
+
+        get_banned(&omegle, &transport).await;
+        assert!(omegle.get_banned_at().is_some());
+        assert_eq!(omegle.get_ban_remaining(), None);
+
+        push_chat(&transport);
+        omegle.new_chat().await.unwrap();
+        assert_eq!(omegle.get_banned_at(), None);
+        assert_eq!(rand_id_sent(&transport), String::from(rand_id))
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn rotates_rand_id_after_ban() {
+        let transport = Arc::new(MemoryTransport::new());
+        let mut omegle = Omegle::with_transport(
+            test_status(),
+            HashSet::new(),
+            LangCode::English,
+            transport.clone(),
+        );
+        omegle.update_ban_policy(
+            BanPolicy::new()
+                .cooldown(Duration::ZERO)
+                .rotate_rand_id(true),
+        );
+        let rand_id = omegle.get_rand_id();
+
+        get_banned(&omegle, &transport).await;
+        push_chat(&transport);
+        omegle.new_chat().await.unwrap();
+
+        assert_ne!(omegle.get_rand_id(), rand_id);
+        assert_eq!(rand_id_sent(&transport), String::from(omegle.get_rand_id()))
+    }
+
+    #[cfg(feature = "proxy")]
+    #[tokio::test(start_paused = true)]
+    async fn switches_proxy_during_cooldown() {
+        use crate::transport::{ProxyPool, Transport};
+
+        let transports = [
+            Arc::new(MemoryTransport::new()),
+            Arc::new(MemoryTransport::new()),
+        ];
+        let pool = Arc::new(ProxyPool::with_transports(
+            transports.iter().enumerate().map(|(index, transport)| {
+                (
+                    format!("http://proxy{index}"),
+                    transport.clone() as Arc<dyn Transport>,
+                )
+            }),
+        ));
+        let mut omegle = Omegle::with_transport(
+            test_status(),
+            HashSet::new(),
+            LangCode::English,
+            Arc::new(MemoryTransport::new()),
+        );
+        omegle.update_proxy_pool(Some(pool.clone()));
+        omegle.update_ban_policy(
+            BanPolicy::new()
+                .cooldown(Duration::from_secs(60))
+                .switch_proxy(true),
+        );
+
+        get_banned(&omegle, &transports[0]).await;
+        push_chat(&transports[1]);
+        omegle.new_chat().await.unwrap();
+        assert_eq!(pool.get_healthy(), vec!["http://proxy1"]);
+
+        get_banned(&omegle, &transports[1]).await;
+        assert!(matches!(
+            omegle.new_chat().await,
+            Err(OmegleLibError::Banned(_))
+        ));
+
+        tokio::time::advance(Duration::from_secs(60)).await;
+        push_chat(&transports[1]);
+        omegle.new_chat().await.unwrap();
+        assert_eq!(pool.get_healthy().len(), 2)
+    }
+}
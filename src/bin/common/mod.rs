@@ -0,0 +1,261 @@
+//! Code shared by the terminal chat clients, requires the `cli` feature
+
+use crossterm::event::{KeyEvent, KeyModifiers};
+use tokio::sync::mpsc::UnboundedSender;
+use tokio::task::JoinHandle;
+
+use omegle_rs::chat_session::ChatSession;
+use omegle_rs::omegle::Omegle;
+use omegle_rs::types::{chat_event::ChatEvent, error::OmegleLibError, lang::LangCode};
+
+/// The commands every client understands
+pub const COMMANDS: [&str; 3] = [
+    "/interests [a, b, ...] - show or replace your interests",
+    "/lang <code> - change the language of your next chats (en, fr, es)",
+    "/help - show this list",
+];
+
+/// An event or polling error, tagged with the chat it belongs to
+pub type ChatUpdate = (u64, Result<ChatEvent, OmegleLibError>);
+
+/// A line to show the user
+#[derive(Debug, PartialEq, Eq)]
+pub enum Notice {
+    Info(String),
+    Error(String),
+}
+
+/// Forwards the events of a chat until it ends
+pub fn poll(
+    session: ChatSession,
+    chat: u64,
+    updates: UnboundedSender<ChatUpdate>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        loop {
+            match session.get_events().await {
+                Ok(events) => {
+                    for event in events {
+                        let chat_ended = event.ends_chat();
+                        if updates.send((chat, Ok(event))).is_err() || chat_ended {
+                            return;
+                        }
+                    }
+                }
+                Err(error) => {
+                    let _ = updates.send((chat, Err(error)));
+                    return;
+                }
+            }
+        }
+    })
+}
+
+/// Tells the stranger we started or stopped typing, without waiting for the server
+pub fn send_typing(session: Option<ChatSession>, typing: bool) {
+    let Some(session) = session else {
+        return;
+    };
+    tokio::spawn(async move {
+        let _ = if typing {
+            session.start_typing().await
+        } else {
+            session.stop_typing().await
+        };
+    });
+}
+
+/// Whether a key was pressed with a modifier other than shift, which only changes the char
+pub fn is_shortcut(key: &KeyEvent) -> bool {
+    !key.modifiers.difference(KeyModifiers::SHIFT).is_empty()
+}
+
+/// Escapes the control characters of text from the stranger or the server, keeping line breaks
+///
+/// Printed as-is, escape sequences could clear the screen, fake lines or set the window title
+pub fn sanitize(text: &str) -> String {
+    let mut sanitized = String::with_capacity(text.len());
+    for char in text.chars() {
+        if char.is_control() && char != '\n' {
+            sanitized.extend(char.escape_default());
+        } else {
+            sanitized.push(char);
+        }
+    }
+    sanitized
+}
+
+/// Splits a command line into the command and its arguments
+pub fn split_command(line: &str) -> (&str, &str) {
+    let (command, args) = line.split_once(' ').unwrap_or((line, ""));
+    (command, args.trim())
+}
+
+/// Runs one of the shared [`COMMANDS`], `/help` also lists the client's own `commands`
+pub fn run_command(
+    omegle: &mut Omegle,
+    command: &str,
+    args: &str,
+    commands: &[&str],
+) -> Vec<Notice> {
+    match command {
+        "/interests" if args.is_empty() => {
+            let mut interests = omegle.get_current_interests();
+            interests.sort();
+            if interests.is_empty() {
+                vec![Notice::Info(String::from("You have no interests."))]
+            } else {
+                let interests = interests.into_iter().cloned().collect::<Vec<_>>();
+                vec![Notice::Info(format!(
+                    "Your interests: {}",
+                    interests.join(", ")
+                ))]
+            }
+        }
+        "/interests" => {
+            let interests = args
+                .split(',')
+                .filter(|interest| !interest.trim().is_empty());
+            match omegle.update_interests(interests) {
+                Ok(()) => vec![Notice::Info(String::from(
+                    "Interests updated, they apply to your next chats.",
+                ))],
+                Err(error) => vec![Notice::Error(format!("{error}, your interests were kept."))],
+            }
+        }
+        "/lang" => match args.parse::<LangCode>() {
+            Ok(lang) => {
+                omegle.update_lang(lang);
+                vec![Notice::Info(format!(
+                    "Language set to '{lang}' for your next chats."
+                ))]
+            }
+            Err(_) => vec![Notice::Error(format!(
+                "Unknown language '{args}', try en, fr or es."
+            ))],
+        },
+        "/help" => commands
+            .iter()
+            .chain(&COMMANDS)
+            .map(|command| Notice::Info(command.to_string()))
+            .collect(),
+        _ => vec![Notice::Error(format!(
+            "Unknown command '{command}', try /help."
+        ))],
+    }
+}
+
+/// Parses the language and interests out of the command line arguments
+///
+/// Prints `usage` and exits when asked for help
+pub fn parse_args(
+    mut args: impl Iterator<Item = String>,
+    usage: &str,
+) -> Result<(LangCode, Vec<String>), String> {
+    let mut lang = LangCode::English;
+    let mut interests = Vec::new();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--lang" => {
+                let code = args.next().ok_or("--lang needs a language code")?;
+                lang = code
+                    .parse()
+                    .map_err(|_| format!("unknown language '{code}'"))?;
+            }
+            "-h" | "--help" => {
+                println!("{usage}");
+                std::process::exit(0);
+            }
+            _ => interests.push(arg),
+        }
+    }
+    Ok((lang, interests))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crossterm::event::KeyCode;
+
+    use omegle_rs::status::OmegleStatus;
+
+    use super::*;
+
+    fn test_omegle() -> Omegle {
+        let status = serde_json::from_str::<OmegleStatus>(
+            r#"{"count": 1, "servers": ["front1"], "antinudeservers": ["waw1.omegle.com"]}"#,
+        )
+        .unwrap();
+        Omegle::new(status, HashSet::new(), LangCode::English)
+    }
+
+    fn args(args: &[&str]) -> impl Iterator<Item = String> {
+        args.iter()
+            .map(|arg| arg.to_string())
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    #[test]
+    fn parses_lang_and_interests() {
+        assert_eq!(
+            parse_args(args(&["music", "--lang", "fr", "books"]), "").unwrap(),
+            (
+                LangCode::French,
+                vec![String::from("music"), String::from("books")]
+            )
+        );
+        assert!(parse_args(args(&["--lang"]), "").is_err());
+        assert!(parse_args(args(&["--lang", "xx"]), "").is_err())
+    }
+
+    #[test]
+    fn keeps_interests_when_one_is_invalid() {
+        let mut omegle = test_omegle();
+        run_command(&mut omegle, "/interests", "music", &[]);
+
+        let notices = run_command(&mut omegle, "/interests", "books, , ,  ", &[]);
+        assert!(matches!(notices[..], [Notice::Info(_)]));
+        assert_eq!(omegle.get_current_interests(), vec!["books"]);
+
+        let too_long = "a".repeat(omegle_rs::omegle::MAX_INTEREST_LENGTH + 1);
+        let notices = run_command(
+            &mut omegle,
+            "/interests",
+            &format!("music, {too_long}"),
+            &[],
+        );
+        assert!(matches!(notices[..], [Notice::Error(_)]));
+        assert_eq!(omegle.get_current_interests(), vec!["books"])
+    }
+
+    #[test]
+    fn help_lists_client_commands_first() {
+        let notices = run_command(&mut test_omegle(), "/help", "", &["/next - skip"]);
+        assert_eq!(notices.len(), COMMANDS.len() + 1);
+        assert_eq!(notices[0], Notice::Info(String::from("/next - skip")))
+    }
+
+    #[test]
+    fn sanitize_escapes_control_characters() {
+        assert_eq!(
+            sanitize("hi\x1b[2J\x1b]0;title\x07\r\u{9b}31m\nbye"),
+            "hi\\u{1b}[2J\\u{1b}]0;title\\u{7}\\r\\u{9b}31m\nbye"
+        );
+        assert_eq!(sanitize("café ✓"), "café ✓")
+    }
+
+    #[test]
+    fn shift_is_not_a_shortcut() {
+        assert!(!is_shortcut(&KeyEvent::new(
+            KeyCode::Char('A'),
+            KeyModifiers::SHIFT
+        )));
+        assert!(is_shortcut(&KeyEvent::new(
+            KeyCode::Char('w'),
+            KeyModifiers::CONTROL
+        )));
+        assert_eq!(split_command("/lang  fr "), ("/lang", "fr"))
+    }
+}
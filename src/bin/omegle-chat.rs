@@ -0,0 +1,302 @@
+//! Interactive terminal chat client, requires the `cli` feature
+//!
+//! Usage: `omegle-chat [--lang <code>] [interest...]`
+
+mod common;
+
+use std::collections::HashSet;
+use std::fmt::Display;
+use std::io::{self, Write};
+
+use crossterm::event::{Event, EventStream, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::style::Stylize;
+use crossterm::terminal::{self, ClearType};
+use crossterm::{cursor, queue};
+use futures::StreamExt;
+use tokio::sync::mpsc::{self, UnboundedSender};
+use tokio::task::JoinHandle;
+
+use omegle_rs::chat_session::ChatSession;
+use omegle_rs::omegle::Omegle;
+use omegle_rs::status::OmegleStatus;
+use omegle_rs::types::chat_event::ChatEvent;
+
+use common::{ChatUpdate, Notice};
+
+const USAGE: &str = "Usage: omegle-chat [--lang <code>] [interest...]";
+
+/// The commands only this client has, listed by `/help` before the shared ones
+const CLIENT_COMMANDS: [&str; 2] = ["/next - skip to a new stranger", "/quit - leave"];
+
+/// Keeps the terminal in raw mode for as long as it lives
+struct RawMode;
+
+impl RawMode {
+    fn enable() -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        Ok(Self)
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+struct Client {
+    omegle: Omegle,
+    session: Option<ChatSession>,
+    poller: Option<JoinHandle<()>>,
+    /// Number of the current chat, updates from older chats are ignored
+    chat: u64,
+    updates: UnboundedSender<ChatUpdate>,
+    input: String,
+    connected: bool,
+    stranger_typing: bool,
+}
+
+impl Client {
+    fn new(omegle: Omegle, updates: UnboundedSender<ChatUpdate>) -> Self {
+        Self {
+            omegle,
+            session: None,
+            poller: None,
+            chat: 0,
+            updates,
+            input: String::new(),
+            connected: false,
+            stranger_typing: false,
+        }
+    }
+
+    /// Leaves the current chat, if any, and looks for a new stranger
+    async fn next_chat(&mut self) {
+        self.end_chat().await;
+        match self.omegle.new_chat().await {
+            Ok(session) => {
+                self.chat += 1;
+                self.poller = Some(common::poll(
+                    session.clone(),
+                    self.chat,
+                    self.updates.clone(),
+                ));
+                self.session = Some(session);
+            }
+            Err(error) => self.error(format!("Could not start a new chat: {error}")),
+        }
+    }
+
+    /// Disconnects from the current chat, if it is still going
+    async fn end_chat(&mut self) {
+        if let Some(poller) = self.poller.take() {
+            poller.abort();
+        }
+        if let Some(session) = self.session.take() {
+            let _ = session.disconnect().await;
+            self.info("You have disconnected.");
+        }
+        self.connected = false;
+        self.stranger_typing = false;
+    }
+
+    async fn handle_update(&mut self, (chat, update): ChatUpdate) {
+        if chat != self.chat {
+            return;
+        }
+        match update {
+            Ok(event) => self.handle_event(event),
+            Err(error) => {
+                self.session = None;
+                self.connected = false;
+                self.error(format!("Lost the chat: {error}"));
+            }
+        }
+    }
+
+    fn handle_event(&mut self, event: ChatEvent) {
+        if event.ends_chat() {
+            self.session = None;
+            self.connected = false;
+            self.stranger_typing = false;
+        }
+
+        match event {
+            ChatEvent::Waiting => self.info("Looking for someone you can chat with..."),
+            ChatEvent::Connected => {
+                self.connected = true;
+                self.info("You're now chatting with a random stranger. Say hi!")
+            }
+            ChatEvent::CommonLikes(likes) => {
+                self.info(format!("You both like {}.", likes.join(", ")))
+            }
+            ChatEvent::ServerMessage(message) => self.info(message),
+            ChatEvent::StartedTyping => {
+                self.stranger_typing = true;
+                self.draw_prompt()
+            }
+            ChatEvent::StoppedTyping => {
+                self.stranger_typing = false;
+                self.draw_prompt()
+            }
+            ChatEvent::Message(message) => {
+                self.stranger_typing = false;
+                let message = common::sanitize(&message);
+                self.print(format!("{} {message}", "Stranger:".red().bold()))
+            }
+            ChatEvent::Disconnected => {
+                self.info("Stranger has disconnected. Type /next to find someone else.")
+            }
+            ChatEvent::ConnectionDied => self.error("The connection to the server died."),
+            ChatEvent::Banned => self.error("You have been banned for a while."),
+            ChatEvent::Error(message) => self.error(format!("Server error: {message}")),
+            _ => {}
+        }
+    }
+
+    /// Handles a key press, returning whether the client should quit
+    async fn handle_key(&mut self, key: KeyEvent) -> bool {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Char('c' | 'd') if ctrl => return true,
+            _ if common::is_shortcut(&key) => {}
+            KeyCode::Char(c) => {
+                self.input.push(c);
+                if self.input.len() == c.len_utf8() {
+                    self.send_typing(true);
+                }
+            }
+            KeyCode::Backspace => {
+                let erased = self.input.pop().is_some();
+                if erased && self.input.is_empty() {
+                    self.send_typing(false);
+                }
+            }
+            KeyCode::Enter => {
+                let line = std::mem::take(&mut self.input);
+                let line = line.trim();
+                if line.starts_with('/') {
+                    return self.handle_command(line).await;
+                } else if !line.is_empty() {
+                    self.send(line).await;
+                }
+            }
+            _ => {}
+        }
+        self.draw_prompt();
+        false
+    }
+
+    /// Runs a command, returning whether the client should quit
+    async fn handle_command(&mut self, line: &str) -> bool {
+        let (command, args) = common::split_command(line);
+        match command {
+            "/next" => self.next_chat().await,
+            "/quit" => return true,
+            _ => {
+                let notices =
+                    common::run_command(&mut self.omegle, command, args, &CLIENT_COMMANDS);
+                for notice in notices {
+                    match notice {
+                        Notice::Info(message) => self.info(message),
+                        Notice::Error(message) => self.error(message),
+                    }
+                }
+            }
+        }
+        self.draw_prompt();
+        false
+    }
+
+    async fn send(&mut self, message: &str) {
+        let Some(session) = self.session.as_ref().filter(|_| self.connected) else {
+            self.error("You're not chatting with anyone, type /next to find someone.");
+            return;
+        };
+        match session.send_message(message).await {
+            Ok(()) => self.print(format!("{} {message}", "You:".blue().bold())),
+            Err(error) => self.error(format!("Could not send your message: {error}")),
+        }
+    }
+
+    fn send_typing(&self, typing: bool) {
+        common::send_typing(self.session.clone().filter(|_| self.connected), typing)
+    }
+
+    fn info<M: Display>(&self, message: M) {
+        self.print(common::sanitize(&message.to_string()).dark_grey())
+    }
+
+    fn error<M: Display>(&self, message: M) {
+        self.print(common::sanitize(&message.to_string()).yellow())
+    }
+
+    /// Prints a line above the prompt
+    fn print<M: Display>(&self, line: M) {
+        let mut stdout = io::stdout();
+        let _ = queue!(
+            stdout,
+            cursor::MoveToColumn(0),
+            terminal::Clear(ClearType::CurrentLine)
+        );
+        // Raw mode doesn't return the cursor on line feeds
+        let line = line.to_string().replace('\n', "\r\n");
+        let _ = write!(stdout, "{line}\r\n");
+        self.draw_prompt();
+    }
+
+    fn draw_prompt(&self) {
+        let mut stdout = io::stdout();
+        let _ = queue!(
+            stdout,
+            cursor::MoveToColumn(0),
+            terminal::Clear(ClearType::CurrentLine)
+        );
+        if self.stranger_typing {
+            let _ = write!(stdout, "{} ", "Stranger is typing...".dark_grey());
+        }
+        let _ = write!(stdout, "{} {}", ">".bold(), self.input);
+        let _ = stdout.flush();
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let (lang, interests) = common::parse_args(std::env::args().skip(1), USAGE)?;
+
+    let status = OmegleStatus::get_omegle_status().await?;
+    println!(
+        "{} strangers online, type /help to see the commands",
+        status.get_count()
+    );
+    let mut omegle = Omegle::new(status, HashSet::new(), lang);
+    for interest in interests {
+        omegle.add_interest(interest)?;
+    }
+
+    let (updates_tx, mut updates) = mpsc::unbounded_channel();
+    let mut client = Client::new(omegle, updates_tx);
+    let mut keys = EventStream::new();
+    let _raw_mode = RawMode::enable()?;
+
+    client.next_chat().await;
+    loop {
+        tokio::select! {
+            Some(update) = updates.recv() => client.handle_update(update).await,
+            key = keys.next() => match key {
+                Some(Ok(Event::Key(key))) if key.kind == KeyEventKind::Press => {
+                    if client.handle_key(key).await {
+                        break;
+                    }
+                }
+                Some(Ok(_)) => {}
+                Some(Err(error)) => return Err(error.into()),
+                None => break,
+            },
+        }
+    }
+
+    client.end_chat().await;
+    print!("\r\n");
+    Ok(())
+}
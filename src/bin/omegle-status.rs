@@ -0,0 +1,286 @@
+//! Prints the status of the omegle servers, requires the `status-cli` feature
+//!
+//! Usage: `omegle-status [--json] [--watch <seconds>]`
+
+use std::time::Duration;
+
+use serde_json::json;
+
+use omegle_rs::status::OmegleStatus;
+
+const USAGE: &str = "Usage: omegle-status [--json] [--watch <seconds>]";
+
+/// The parts of a status that get printed
+#[derive(PartialEq)]
+struct Snapshot {
+    count: u64,
+    chat_servers: Vec<String>,
+    check_servers: Vec<String>,
+    spy_queue_time: Option<f64>,
+    spyee_queue_time: Option<f64>,
+    rtmfp: Option<String>,
+}
+
+impl From<&OmegleStatus> for Snapshot {
+    fn from(status: &OmegleStatus) -> Self {
+        Self {
+            count: status.get_count(),
+            chat_servers: status
+                .get_chat_servers()
+                .iter()
+                .map(|&server| String::from(server))
+                .collect(),
+            check_servers: status
+                .get_check_servers()
+                .iter()
+                .map(|&server| String::from(server))
+                .collect(),
+            spy_queue_time: status.get_spy_queue_time().map(|time| time.as_secs_f64()),
+            spyee_queue_time: status.get_spyee_queue_time().map(|time| time.as_secs_f64()),
+            rtmfp: status.get_rtmfp().map(String::from),
+        }
+    }
+}
+
+/// Servers in `current` but not in `previous`, and the other way around
+fn server_changes(current: &[String], previous: &[String]) -> (Vec<String>, Vec<String>) {
+    let added = current
+        .iter()
+        .filter(|server| !previous.contains(server))
+        .cloned()
+        .collect();
+    let removed = previous
+        .iter()
+        .filter(|server| !current.contains(server))
+        .cloned()
+        .collect();
+    (added, removed)
+}
+
+fn format_servers(current: &[String], previous: Option<&[String]>) -> String {
+    let mut line = current.join(", ");
+    if let Some(previous) = previous {
+        let (added, removed) = server_changes(current, previous);
+        let changes = added
+            .iter()
+            .map(|server| format!("+{server}"))
+            .chain(removed.iter().map(|server| format!("-{server}")))
+            .collect::<Vec<_>>();
+        if !changes.is_empty() {
+            line.push_str(&format!(" ({})", changes.join(", ")));
+        }
+    }
+    line
+}
+
+fn format_queue_time(current: Option<f64>, previous: Option<f64>) -> String {
+    match (current, previous) {
+        (Some(current), Some(previous)) if current != previous => {
+            format!("{current:.1}s ({:+.1}s)", current - previous)
+        }
+        (Some(current), _) => format!("{current:.1}s"),
+        (None, _) => String::from("unknown"),
+    }
+}
+
+/// Formats a snapshot as lines of text, with the changes since `previous`
+fn format_text(snapshot: &Snapshot, previous: Option<&Snapshot>) -> String {
+    let count_delta = previous
+        .map(|previous| format!(" ({:+})", snapshot.count as i64 - previous.count as i64))
+        .unwrap_or_default();
+    let mut text = format!("Online:        {}{count_delta}\n", snapshot.count);
+    text.push_str(&format!(
+        "Chat servers:  {}\n",
+        format_servers(
+            &snapshot.chat_servers,
+            previous.map(|previous| previous.chat_servers.as_slice())
+        )
+    ));
+    text.push_str(&format!(
+        "Check servers: {}\n",
+        format_servers(
+            &snapshot.check_servers,
+            previous.map(|previous| previous.check_servers.as_slice())
+        )
+    ));
+    text.push_str(&format!(
+        "Spy queue:     {}\n",
+        format_queue_time(
+            snapshot.spy_queue_time,
+            previous.and_then(|previous| previous.spy_queue_time)
+        )
+    ));
+    text.push_str(&format!(
+        "Spyee queue:   {}\n",
+        format_queue_time(
+            snapshot.spyee_queue_time,
+            previous.and_then(|previous| previous.spyee_queue_time)
+        )
+    ));
+    if let Some(rtmfp) = &snapshot.rtmfp {
+        text.push_str(&format!("RTMFP:         {rtmfp}\n"));
+    }
+    text
+}
+
+/// Formats a snapshot as a json object, with the changes since `previous` under `delta`
+fn format_json(snapshot: &Snapshot, previous: Option<&Snapshot>) -> String {
+    let mut output = json!({
+        "count": snapshot.count,
+        "chat_servers": snapshot.chat_servers,
+        "check_servers": snapshot.check_servers,
+        "spy_queue_time": snapshot.spy_queue_time,
+        "spyee_queue_time": snapshot.spyee_queue_time,
+        "rtmfp": snapshot.rtmfp,
+    });
+    if let Some(previous) = previous {
+        let (chat_added, chat_removed) =
+            server_changes(&snapshot.chat_servers, &previous.chat_servers);
+        let (check_added, check_removed) =
+            server_changes(&snapshot.check_servers, &previous.check_servers);
+        output["delta"] = json!({
+            "count": snapshot.count as i64 - previous.count as i64,
+            "chat_servers_added": chat_added,
+            "chat_servers_removed": chat_removed,
+            "check_servers_added": check_added,
+            "check_servers_removed": check_removed,
+            "spy_queue_time": snapshot.spy_queue_time.zip(previous.spy_queue_time).map(|(current, previous)| current - previous),
+            "spyee_queue_time": snapshot.spyee_queue_time.zip(previous.spyee_queue_time).map(|(current, previous)| current - previous),
+        });
+    }
+    // One object per line so `--watch` output can be streamed
+    format!("{output}\n")
+}
+
+/// Parses whether to print json and how often to refresh out of the command line arguments
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<(bool, Option<Duration>), String> {
+    let mut json = false;
+    let mut watch = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--json" => json = true,
+            "--watch" => {
+                let seconds = args.next().ok_or("--watch needs an amount of seconds")?;
+                let seconds = seconds
+                    .parse::<u64>()
+                    .ok()
+                    .filter(|&seconds| seconds > 0)
+                    .ok_or(format!("invalid amount of seconds '{seconds}'"))?;
+                watch = Some(Duration::from_secs(seconds));
+            }
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                std::process::exit(0);
+            }
+            _ => return Err(format!("unexpected argument '{arg}'\n{USAGE}")),
+        }
+    }
+    Ok((json, watch))
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let (json, watch) = parse_args(std::env::args().skip(1))?;
+    let format = if json { format_json } else { format_text };
+
+    let Some(interval) = watch else {
+        let status = OmegleStatus::get_omegle_status().await?;
+        print!("{}", format(&Snapshot::from(&status), None));
+        return Ok(());
+    };
+
+    let mut ticks = tokio::time::interval(interval);
+    let mut previous: Option<Snapshot> = None;
+    loop {
+        ticks.tick().await;
+        match OmegleStatus::get_omegle_status().await {
+            Ok(status) => {
+                let snapshot = Snapshot::from(&status);
+                if previous.is_some() && !json {
+                    println!();
+                }
+                print!("{}", format(&snapshot, previous.as_ref()));
+                previous = Some(snapshot);
+            }
+            // Omegle being down is worth reporting, not giving up on
+            Err(error) => eprintln!("Could not get omegle status: {error}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(count: u64, chat_servers: &[&str], spy_queue_time: Option<f64>) -> Snapshot {
+        Snapshot {
+            count,
+            chat_servers: chat_servers
+                .iter()
+                .map(|server| server.to_string())
+                .collect(),
+            check_servers: vec![String::from("waw1.omegle.com")],
+            spy_queue_time,
+            spyee_queue_time: None,
+            rtmfp: None,
+        }
+    }
+
+    fn args(args: &[&str]) -> impl Iterator<Item = String> {
+        args.iter()
+            .map(|arg| arg.to_string())
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    #[test]
+    fn finds_added_and_removed_servers() {
+        let current = vec![String::from("front1"), String::from("front3")];
+        let previous = vec![String::from("front1"), String::from("front2")];
+        assert_eq!(
+            server_changes(&current, &previous),
+            (vec![String::from("front3")], vec![String::from("front2")])
+        );
+        assert_eq!(server_changes(&current, &current), (vec![], vec![]))
+    }
+
+    #[test]
+    fn formats_queue_time_changes() {
+        assert_eq!(format_queue_time(Some(2.5), None), "2.5s");
+        assert_eq!(format_queue_time(Some(2.5), Some(2.5)), "2.5s");
+        assert_eq!(format_queue_time(Some(2.5), Some(4.0)), "2.5s (-1.5s)");
+        assert_eq!(format_queue_time(None, Some(4.0)), "unknown")
+    }
+
+    #[test]
+    fn parses_json_and_watch() {
+        assert_eq!(parse_args(args(&[])).unwrap(), (false, None));
+        assert_eq!(
+            parse_args(args(&["--watch", "5", "--json"])).unwrap(),
+            (true, Some(Duration::from_secs(5)))
+        );
+        assert!(parse_args(args(&["--watch"])).is_err());
+        assert!(parse_args(args(&["--watch", "0"])).is_err());
+        assert!(parse_args(args(&["--verbose"])).is_err())
+    }
+
+    #[test]
+    fn watch_output_shows_changes() {
+        let previous = snapshot(100, &["front1", "front2"], Some(4.0));
+        let current = snapshot(90, &["front1", "front3"], Some(2.5));
+
+        let text = format_text(&current, Some(&previous));
+        assert!(text.contains("Online:        90 (-10)"));
+        assert!(text.contains("Chat servers:  front1, front3 (+front3, -front2)"));
+        assert!(text.contains("Check servers: waw1.omegle.com\n"));
+        assert!(text.contains("Spy queue:     2.5s (-1.5s)"));
+
+        let json =
+            serde_json::from_str::<serde_json::Value>(&format_json(&current, Some(&previous)))
+                .unwrap();
+        assert_eq!(json["delta"]["count"], -10);
+        assert_eq!(json["delta"]["chat_servers_added"], json!(["front3"]));
+        assert_eq!(json["delta"]["chat_servers_removed"], json!(["front2"]));
+        assert_eq!(json["delta"]["spy_queue_time"], -1.5)
+    }
+}
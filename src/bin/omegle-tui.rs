@@ -0,0 +1,426 @@
+//! Full-screen terminal chat client, requires the `tui` feature
+//!
+//! Usage: `omegle-tui [--lang <code>] [interest...]`
+
+mod common;
+
+use std::collections::HashSet;
+use std::fmt;
+
+use crossterm::event::{Event, EventStream, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use futures::StreamExt;
+use ratatui::layout::{Constraint, Layout, Position, Rect};
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Paragraph, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tokio::task::JoinHandle;
+use vec1::Vec1;
+
+use omegle_rs::chat_session::ChatSession;
+use omegle_rs::omegle::Omegle;
+use omegle_rs::status::OmegleStatus;
+use omegle_rs::types::chat_event::ChatEvent;
+
+use common::{ChatUpdate, Notice};
+
+const USAGE: &str = "Usage: omegle-tui [--lang <code>] [interest...]";
+
+const KEYBINDINGS: &str = "Esc: next stranger  Ctrl+D: disconnect  PgUp/PgDn: scroll  Ctrl+C: quit";
+
+/// A line of the transcript
+enum Entry {
+    Stranger(String),
+    You(String),
+    Info(String),
+    Error(String),
+}
+
+impl Entry {
+    fn to_line(&self) -> Line<'_> {
+        match self {
+            Entry::Stranger(message) => Line::from(vec![
+                Span::styled("Stranger: ", Style::new().fg(Color::Red).bold()),
+                Span::raw(message),
+            ]),
+            Entry::You(message) => Line::from(vec![
+                Span::styled("You: ", Style::new().fg(Color::Blue).bold()),
+                Span::raw(message),
+            ]),
+            Entry::Info(message) => Line::styled(message, Style::new().fg(Color::DarkGray)),
+            Entry::Error(message) => Line::styled(message, Style::new().fg(Color::Yellow)),
+        }
+    }
+
+    /// Amount of rows the entry takes up once wrapped to `width` columns
+    fn height(&self, width: u16) -> usize {
+        let chars = match self {
+            Entry::Stranger(message) => "Stranger: ".len() + message.chars().count(),
+            Entry::You(message) => "You: ".len() + message.chars().count(),
+            Entry::Info(message) | Entry::Error(message) => message.chars().count(),
+        };
+        chars.max(1).div_ceil(usize::from(width.max(1)))
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ConnectionState {
+    Idle,
+    Searching,
+    Waiting,
+    Connected,
+    Disconnected,
+}
+
+impl fmt::Display for ConnectionState {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = match self {
+            ConnectionState::Idle => "Idle",
+            ConnectionState::Searching => "Starting chat",
+            ConnectionState::Waiting => "Waiting for a stranger",
+            ConnectionState::Connected => "Connected",
+            ConnectionState::Disconnected => "Disconnected",
+        };
+        write!(formatter, "{state}")
+    }
+}
+
+struct App {
+    omegle: Omegle,
+    session: Option<ChatSession>,
+    poller: Option<JoinHandle<()>>,
+    /// Number of the current chat, updates from older chats are ignored
+    chat: u64,
+    updates: UnboundedSender<ChatUpdate>,
+    transcript: Vec<Entry>,
+    /// How many entries the transcript is scrolled up by
+    scroll: usize,
+    input: String,
+    state: ConnectionState,
+    online: u64,
+    common_likes: Option<Vec1<String>>,
+    stranger_typing: bool,
+}
+
+impl App {
+    fn new(omegle: Omegle, online: u64, updates: UnboundedSender<ChatUpdate>) -> Self {
+        Self {
+            omegle,
+            session: None,
+            poller: None,
+            chat: 0,
+            updates,
+            transcript: Vec::new(),
+            scroll: 0,
+            input: String::new(),
+            state: ConnectionState::Idle,
+            online,
+            common_likes: None,
+            stranger_typing: false,
+        }
+    }
+
+    /// Leaves the current chat, if any, and looks for a new stranger
+    async fn next_chat(&mut self) {
+        self.end_chat().await;
+        self.state = ConnectionState::Searching;
+        match self.omegle.new_chat().await {
+            Ok(session) => {
+                self.chat += 1;
+                self.poller = Some(common::poll(
+                    session.clone(),
+                    self.chat,
+                    self.updates.clone(),
+                ));
+                self.session = Some(session);
+            }
+            Err(error) => {
+                self.state = ConnectionState::Idle;
+                self.push(Entry::Error(format!("Could not start a new chat: {error}")))
+            }
+        }
+    }
+
+    /// Disconnects from the current chat, if it is still going
+    async fn end_chat(&mut self) {
+        if let Some(poller) = self.poller.take() {
+            poller.abort();
+        }
+        if let Some(session) = self.session.take() {
+            let _ = session.disconnect().await;
+            self.push(Entry::Info(String::from("You have disconnected.")));
+        }
+        self.chat_over();
+    }
+
+    fn chat_over(&mut self) {
+        if self.state != ConnectionState::Idle {
+            self.state = ConnectionState::Disconnected;
+        }
+        self.session = None;
+        self.common_likes = None;
+        self.stranger_typing = false;
+    }
+
+    fn handle_update(&mut self, (chat, update): ChatUpdate) {
+        if chat != self.chat {
+            return;
+        }
+        match update {
+            Ok(event) => self.handle_event(event),
+            Err(error) => {
+                self.chat_over();
+                self.push(Entry::Error(format!("Lost the chat: {error}")))
+            }
+        }
+    }
+
+    fn handle_event(&mut self, event: ChatEvent) {
+        if event.ends_chat() {
+            self.chat_over();
+        }
+
+        match event {
+            ChatEvent::Waiting => self.state = ConnectionState::Waiting,
+            ChatEvent::Connected => {
+                self.state = ConnectionState::Connected;
+                self.push(Entry::Info(String::from(
+                    "You're now chatting with a random stranger. Say hi!",
+                )))
+            }
+            ChatEvent::StatusInfo(status) => self.online = status.get_count(),
+            ChatEvent::Count(count) => self.online = count,
+            ChatEvent::CommonLikes(likes) => {
+                self.push(Entry::Info(format!("You both like {}.", likes.join(", "))));
+                self.common_likes = Some(likes.mapped(|like| common::sanitize(&like)));
+            }
+            ChatEvent::ServerMessage(message) => self.push(Entry::Info(message)),
+            ChatEvent::StartedTyping => self.stranger_typing = true,
+            ChatEvent::StoppedTyping => self.stranger_typing = false,
+            ChatEvent::Message(message) => {
+                self.stranger_typing = false;
+                self.push(Entry::Stranger(message))
+            }
+            ChatEvent::Disconnected => self.push(Entry::Info(String::from(
+                "Stranger has disconnected. Press Esc to find someone else.",
+            ))),
+            ChatEvent::ConnectionDied => self.push(Entry::Error(String::from(
+                "The connection to the server died.",
+            ))),
+            ChatEvent::Banned => self.push(Entry::Error(String::from(
+                "You have been banned for a while.",
+            ))),
+            ChatEvent::Error(message) => {
+                self.push(Entry::Error(format!("Server error: {message}")))
+            }
+            _ => {}
+        }
+    }
+
+    /// Handles a key press, returning whether the app should quit
+    async fn handle_key(&mut self, key: KeyEvent) -> bool {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Char('c') if ctrl => return true,
+            KeyCode::Char('d') if ctrl => self.end_chat().await,
+            KeyCode::Esc => self.next_chat().await,
+            KeyCode::PageUp => self.scroll = (self.scroll + 5).min(self.transcript.len()),
+            KeyCode::PageDown => self.scroll = self.scroll.saturating_sub(5),
+            _ if common::is_shortcut(&key) => {}
+            KeyCode::Char(c) => {
+                self.input.push(c);
+                if self.input.len() == c.len_utf8() {
+                    self.send_typing(true);
+                }
+            }
+            KeyCode::Backspace => {
+                let erased = self.input.pop().is_some();
+                if erased && self.input.is_empty() {
+                    self.send_typing(false);
+                }
+            }
+            KeyCode::Enter => {
+                let line = std::mem::take(&mut self.input);
+                let line = line.trim();
+                if line.starts_with('/') {
+                    self.handle_command(line);
+                } else if !line.is_empty() {
+                    self.send(line).await;
+                }
+            }
+            _ => {}
+        }
+        false
+    }
+
+    fn handle_command(&mut self, line: &str) {
+        let (command, args) = common::split_command(line);
+        for notice in common::run_command(&mut self.omegle, command, args, &[]) {
+            self.push(match notice {
+                Notice::Info(message) => Entry::Info(message),
+                Notice::Error(message) => Entry::Error(message),
+            })
+        }
+    }
+
+    async fn send(&mut self, message: &str) {
+        let Some(session) = self
+            .session
+            .as_ref()
+            .filter(|_| self.state == ConnectionState::Connected)
+        else {
+            self.push(Entry::Error(String::from(
+                "You're not chatting with anyone, press Esc to find someone.",
+            )));
+            return;
+        };
+        match session.send_message(message).await {
+            Ok(()) => self.push(Entry::You(message.to_string())),
+            Err(error) => self.push(Entry::Error(format!(
+                "Could not send your message: {error}"
+            ))),
+        }
+    }
+
+    fn send_typing(&self, typing: bool) {
+        let session = self
+            .session
+            .clone()
+            .filter(|_| self.state == ConnectionState::Connected);
+        common::send_typing(session, typing)
+    }
+
+    fn push(&mut self, entry: Entry) {
+        // Each entry is drawn as a single line
+        let clean = |message: String| common::sanitize(&message).replace('\n', " ");
+        self.transcript.push(match entry {
+            Entry::Stranger(message) => Entry::Stranger(clean(message)),
+            Entry::You(message) => Entry::You(clean(message)),
+            Entry::Info(message) => Entry::Info(clean(message)),
+            Entry::Error(message) => Entry::Error(clean(message)),
+        });
+        // Keep the same entries in view while scrolled up
+        if self.scroll > 0 {
+            self.scroll += 1;
+        }
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let [transcript_area, input_area, status_area] = Layout::vertical([
+            Constraint::Min(3),
+            Constraint::Length(3),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+
+        self.draw_transcript(frame, transcript_area);
+
+        let input_title = if self.stranger_typing {
+            "Stranger is typing..."
+        } else {
+            "Message"
+        };
+        frame.render_widget(
+            Paragraph::new(self.input.as_str()).block(Block::bordered().title(input_title)),
+            input_area,
+        );
+        let cursor_x = input_area.x + 1 + self.input.chars().count() as u16;
+        frame.set_cursor_position(Position::new(
+            cursor_x.min(input_area.right().saturating_sub(2)),
+            input_area.y + 1,
+        ));
+
+        let state_color = match self.state {
+            ConnectionState::Connected => Color::Green,
+            ConnectionState::Disconnected => Color::Red,
+            _ => Color::Yellow,
+        };
+        let mut status = vec![
+            Span::styled(self.state.to_string(), Style::new().fg(state_color).bold()),
+            Span::raw(format!(" | {} online", self.online)),
+        ];
+        if let Some(likes) = &self.common_likes {
+            status.push(Span::raw(format!(" | Likes: {}", likes.join(", "))));
+        }
+        status.push(Span::styled(
+            format!(" | {KEYBINDINGS}"),
+            Style::new().add_modifier(Modifier::DIM),
+        ));
+        frame.render_widget(Line::from(status), status_area);
+    }
+
+    /// Draws the end of the transcript that fits in `area`, minus the scrolled entries
+    fn draw_transcript(&self, frame: &mut Frame, area: Rect) {
+        let block = Block::bordered().title("omegle");
+        let inner = block.inner(area);
+        let visible_end = self.transcript.len() - self.scroll.min(self.transcript.len());
+
+        let mut height = 0;
+        let mut visible_start = visible_end;
+        while visible_start > 0 && height < usize::from(inner.height) {
+            visible_start -= 1;
+            height += self.transcript[visible_start].height(inner.width);
+        }
+        let overflow = height.saturating_sub(usize::from(inner.height));
+
+        let lines = self.transcript[visible_start..visible_end]
+            .iter()
+            .map(Entry::to_line)
+            .collect::<Vec<_>>();
+        let transcript = Paragraph::new(lines)
+            .block(block)
+            .wrap(Wrap { trim: false })
+            .scroll((u16::try_from(overflow).unwrap_or(u16::MAX), 0));
+        frame.render_widget(transcript, area);
+    }
+}
+
+async fn run(
+    terminal: &mut DefaultTerminal,
+    app: &mut App,
+    mut updates: UnboundedReceiver<ChatUpdate>,
+) -> std::io::Result<()> {
+    let mut keys = EventStream::new();
+
+    terminal.draw(|frame| app.draw(frame))?;
+    app.next_chat().await;
+    loop {
+        terminal.draw(|frame| app.draw(frame))?;
+        tokio::select! {
+            Some(update) = updates.recv() => app.handle_update(update),
+            key = keys.next() => match key {
+                Some(Ok(Event::Key(key))) if key.kind == KeyEventKind::Press => {
+                    if app.handle_key(key).await {
+                        break;
+                    }
+                }
+                Some(Ok(_)) => {}
+                Some(Err(error)) => return Err(error),
+                None => break,
+            },
+        }
+    }
+
+    app.end_chat().await;
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let (lang, interests) = common::parse_args(std::env::args().skip(1), USAGE)?;
+
+    let status = OmegleStatus::get_omegle_status().await?;
+    let online = status.get_count();
+    let mut omegle = Omegle::new(status, HashSet::new(), lang);
+    for interest in interests {
+        omegle.add_interest(interest)?;
+    }
+
+    let (updates_tx, updates) = mpsc::unbounded_channel();
+    let mut app = App::new(omegle, online, updates_tx);
+    let mut terminal = ratatui::init();
+    let resp = run(&mut terminal, &mut app, updates).await;
+    ratatui::restore();
+    Ok(resp?)
+}
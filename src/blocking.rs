@@ -0,0 +1,265 @@
+//! Blocking versions of the chat API
+//!
+//! Useful for small tools and scripts that don't want to manage an async runtime.
+//! Requires the `blocking` feature.
+//!
+//! Use [`OmegleStatus::get_omegle_status_blocking`](crate::status::OmegleStatus::get_omegle_status_blocking)
+//! and [`Omegle::new_chat_blocking`](crate::omegle::Omegle::new_chat_blocking) to get a [`ChatSession`]
+//!
+//! # Notes:
+//! Only [`OmegleStatus::get_omegle_status_blocking`](crate::status::OmegleStatus::get_omegle_status_blocking)
+//! is built on [`reqwest::blocking`]. A blocking [`ChatSession`] is not, it wraps the
+//! async [`ChatSession`](crate::chat_session::ChatSession) and blocks on it with a
+//! small tokio runtime owned by the [`Omegle`](crate::omegle::Omegle) that started it.
+//! That way it goes through the same [`Transport`](crate::transport::Transport), ban
+//! handling and proxies. It must not be used from within an async runtime.
+
+use std::sync::Arc;
+
+use tokio::runtime::Runtime;
+use vec1::Vec1;
+
+use crate::types::rtc::{IceCandidate, RtcSessionDescription};
+use crate::types::{chat_event::ChatEvent, error::OmegleLibError};
+
+/// Blocking version of [`ChatSession`](crate::chat_session::ChatSession)
+#[derive(Debug, Clone)]
+pub struct ChatSession {
+    pub(crate) session: crate::chat_session::ChatSession,
+    pub(crate) runtime: Arc<Runtime>,
+}
+
+impl ChatSession {
+    /// Gets the async session this one blocks on
+    pub fn get_session(&self) -> &crate::chat_session::ChatSession {
+        &self.session
+    }
+
+    /// Gets the async session this one blocks on, to configure it
+    ///
+    /// Every setting of the async session (Ex: [idle timeout](crate::chat_session::ChatSession::update_idle_timeout),
+    /// [filter](crate::chat_session::ChatSession::update_filter)) applies to the
+    /// blocking calls as well
+    pub fn get_session_mut(&mut self) -> &mut crate::chat_session::ChatSession {
+        &mut self.session
+    }
+
+    /// Sends a message to the other party.
+    ///
+    /// # Errors
+    /// This function fails if:
+    /// - The omegle server cannot be reached
+    /// - The response from omegle indicated an error
+    pub fn send_message<M: ToString>(&self, message: M) -> Result<(), OmegleLibError> {
+        self.runtime.block_on(self.session.send_message(message))
+    }
+
+    /// Sends a typing indicator to the server
+    ///
+    /// See [`ChatSession::start_typing`](crate::chat_session::ChatSession::start_typing)
+    ///
+    /// # Errors
+    /// This function fails if:
+    /// - The omegle server cannot be reached
+    /// - The response from omegle indicated an error
+    pub fn start_typing(&self) -> Result<(), OmegleLibError> {
+        self.runtime.block_on(self.session.start_typing())
+    }
+
+    /// Sends a stopped typing indicator to the server
+    ///
+    /// See [`ChatSession::stop_typing`](crate::chat_session::ChatSession::stop_typing)
+    ///
+    /// # Errors
+    /// This function fails if:
+    /// - The omegle server cannot be reached
+    /// - The response from omegle indicated an error
+    pub fn stop_typing(&self) -> Result<(), OmegleLibError> {
+        self.runtime.block_on(self.session.stop_typing())
+    }
+
+    /// Gets a list of [`ChatEvent`] from the server.
+    ///
+    /// Omegle uses long-polling, so this blocks the current thread untill
+    /// the server responds with new events.
+    ///
+    /// Events omegle sent along with the start of the chat are returned
+    /// by the first call, without polling the server. A [`ChatEvent::Banned`] is
+    /// recorded by the [`Omegle`](crate::omegle::Omegle) that started the chat.
+    ///
+    /// # Errors
+    /// This function fails if:
+    /// - The omegle server cannot be reached
+    /// - The response from omegle was malformed
+    /// - The function was called after the chat ended
+    pub fn get_events(&self) -> Result<Vec1<ChatEvent>, OmegleLibError> {
+        self.runtime.block_on(self.session.get_events())
+    }
+
+    /// Sends a disconnect request to the server
+    ///
+    /// # Errors
+    /// This function fails if:
+    /// - The omegle server cannot be reached
+    /// - The response from omegle indicated an error
+    pub fn disconnect(&self) -> Result<(), OmegleLibError> {
+        self.runtime.block_on(self.session.disconnect())
+    }
+
+    /// Sends our WebRTC session description (offer or answer) to the other party
+    ///
+    /// See [`ChatSession::send_peer_description`](crate::chat_session::ChatSession::send_peer_description)
+    ///
+    /// # Errors
+    /// This function fails if:
+    /// - The omegle server cannot be reached
+    /// - The response from omegle indicated an error
+    pub fn send_peer_description(
+        &self,
+        description: &RtcSessionDescription,
+    ) -> Result<(), OmegleLibError> {
+        self.runtime
+            .block_on(self.session.send_peer_description(description))
+    }
+
+    /// Sends one of our WebRTC ICE candidates to the other party
+    ///
+    /// # Errors
+    /// This function fails if:
+    /// - The omegle server cannot be reached
+    /// - The response from omegle indicated an error
+    pub fn send_ice_candidate(&self, candidate: &IceCandidate) -> Result<(), OmegleLibError> {
+        self.runtime
+            .block_on(self.session.send_ice_candidate(candidate))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::time::Duration;
+
+    use crate::ban::BanPolicy;
+    use crate::omegle::{tests::test_status, Omegle};
+    use crate::transport::{MemoryTransport, RequestMethod};
+    use crate::types::lang::LangCode;
+
+    use super::*;
+
+    fn test_omegle(transport: Arc<MemoryTransport>) -> Omegle {
+        Omegle::with_transport(test_status(), HashSet::new(), LangCode::English, transport)
+    }
+
+    #[test]
+    fn new_chat_blocking_uses_the_transport() {
+        let transport = Arc::new(MemoryTransport::new());
+        for _ in 0..2 {
+            transport.push_response("/check", "checkcode");
+            transport.push_response("/start", r#""central1:aaaaaaaaaaaaaaaaaaaaaaaaaaaaaa""#);
+        }
+        let omegle = test_omegle(transport.clone());
+
+        let first = omegle.new_chat_blocking().unwrap();
+        let second = omegle.new_chat_blocking().unwrap();
+
+        assert!(Arc::ptr_eq(&first.runtime, &second.runtime));
+        let requests = transport.requests();
+        assert_eq!(requests.len(), 4);
+        assert_eq!(requests[0].url, "http://waw1.omegle.com/check");
+        assert_eq!(requests[1].method, RequestMethod::Post)
+    }
+
+    #[test]
+    fn session_sends_actions_and_gets_events() {
+        let transport = Arc::new(MemoryTransport::new());
+        transport.push_response("/check", "checkcode");
+        transport.push_response(
+            "/start",
+            r#"{"clientID": "central1:aaaaaaaaaaaaaaaaaaaaaaaaaaaaaa", "events": [["waiting"]]}"#,
+        );
+        transport.push_response("/events", r#"[["connected"], ["gotMessage", "hi"]]"#);
+        transport.push_response("/send", "win");
+        transport.push_response("/disconnect", "fail");
+        let session = test_omegle(transport.clone()).new_chat_blocking().unwrap();
+
+        assert_eq!(
+            session.get_events().unwrap().into_vec(),
+            vec![ChatEvent::Waiting]
+        );
+        assert_eq!(
+            session.get_events().unwrap().into_vec(),
+            vec![ChatEvent::Connected, ChatEvent::Message(String::from("hi"))]
+        );
+        session.send_message("hello").unwrap();
+        assert!(matches!(
+            session.disconnect(),
+            Err(OmegleLibError::OmegleError(resp)) if resp == "fail"
+        ));
+
+        let send = &transport.requests()[3];
+        assert_eq!(send.url, "http://front1.omegle.com/send");
+        assert!(send
+            .form
+            .contains(&(String::from("msg"), String::from("hello"))))
+    }
+
+    #[test]
+    fn new_chat_blocking_fails_until_cooldown_is_over() {
+        let transport = Arc::new(MemoryTransport::new());
+        transport.push_response("/check", "checkcode");
+        transport.push_response("/start", r#""central1:aaaaaaaaaaaaaaaaaaaaaaaaaaaaaa""#);
+        transport.push_response("/events", r#"[["antinudeBanned"]]"#);
+        let mut omegle = test_omegle(transport.clone());
+        let cooldown = Duration::from_secs(60);
+        omegle.update_ban_policy(BanPolicy::new().cooldown(cooldown).rotate_rand_id(true));
+        let rand_id = omegle.get_rand_id();
+
+        let session = omegle.new_chat_blocking().unwrap();
+        assert_eq!(
+            session.get_events().unwrap().into_vec(),
+            vec![ChatEvent::Banned]
+        );
+        assert!(matches!(
+            omegle.new_chat_blocking(),
+            Err(OmegleLibError::Banned(remaining)) if remaining > Duration::ZERO && remaining <= cooldown
+        ));
+        assert_ne!(omegle.get_rand_id(), rand_id);
+
+        // The cooldown is over as soon as it is shorter than the time since the ban
+        omegle.update_ban_policy(BanPolicy::new().cooldown(Duration::ZERO));
+        transport.push_response("/check", "checkcode");
+        transport.push_response("/start", r#""central1:aaaaaaaaaaaaaaaaaaaaaaaaaaaaaa""#);
+        omegle.new_chat_blocking().unwrap();
+        assert_eq!(omegle.get_banned_at(), None)
+    }
+
+    #[test]
+    fn session_settings_apply_to_blocking_calls() {
+        let transport = Arc::new(MemoryTransport::new());
+        transport.push_response("/check", "checkcode");
+        transport.push_response("/start", r#""central1:aaaaaaaaaaaaaaaaaaaaaaaaaaaaaa""#);
+        transport.push_stall("/send");
+        let mut session = test_omegle(transport).new_chat_blocking().unwrap();
+        session
+            .get_session_mut()
+            .update_action_timeout(Some(Duration::from_millis(10)));
+
+        assert_eq!(
+            session.get_session().get_action_timeout(),
+            Some(Duration::from_millis(10))
+        );
+        assert!(matches!(
+            session.send_message("hi"),
+            Err(OmegleLibError::Timeout(_))
+        ))
+    }
+
+    #[test]
+    fn new_chat_blocking_reports_transport_errors() {
+        let omegle = test_omegle(Arc::new(MemoryTransport::new()));
+        assert!(matches!(
+            omegle.new_chat_blocking(),
+            Err(OmegleLibError::TransportError(_))
+        ))
+    }
+}
@@ -0,0 +1,392 @@
+//! Small framework for writing bots on top of a [`ChatSession`]
+//!
+//! A [`Bot`] routes prefixed messages (Ex: `!help`) to commands, keeps some state
+//! for every conversation, greets strangers when they connect and can disconnect
+//! from strangers that stay idle for too long. Requires the `bot` feature.
+//!
+//! # Examples
+//! Count the messages of every stranger
+//! ```rust
+//! use omegle_rs::bot::Bot;
+//! use omegle_rs::chat_session::ChatSession;
+//! use std::time::Duration;
+//!
+//! async fn run(session: ChatSession) {
+//!     let bot = Bot::<u32>::new("!")
+//!         .greeting(|_| Some(String::from("Hi! Send !count to see how chatty you are")))
+//!         .command("count", "Shows how many messages you sent", |count, _args| {
+//!             Some(format!("You sent {count} messages"))
+//!         })
+//!         .fallback(|count, _message| {
+//!             *count += 1;
+//!             None
+//!         })
+//!         .idle_timeout(Duration::from_secs(60));
+//!     let messages_sent = bot.run(&session).await.unwrap();
+//! }
+//! ```
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use async_trait::async_trait;
+
+use crate::chat_session::ChatSession;
+use crate::event_handler::EventHandler;
+use crate::types::{chat_event::ChatEvent, error::OmegleLibError};
+
+type CommandFn<S> = Box<dyn Fn(&mut S, &str) -> Option<String> + Send + Sync>;
+type HookFn<S> = Box<dyn Fn(&mut S) -> Option<String> + Send + Sync>;
+
+struct Command<S> {
+    description: String,
+    handler: CommandFn<S>,
+}
+
+/// A bot that reacts to the events of a [`ChatSession`]
+///
+/// `S` is the state kept for every conversation, a fresh [`Default`] value is
+/// created every time [`Bot::run`] is called.
+pub struct Bot<S> {
+    prefix: String,
+    commands: BTreeMap<String, Command<S>>,
+    fallback: Option<CommandFn<S>>,
+    greeting: Option<HookFn<S>>,
+    farewell: Option<HookFn<S>>,
+    idle_timeout: Option<Duration>,
+}
+
+impl<S: Default + Send> Bot<S> {
+    /// Creates a new [`Bot`] that treats messages starting with `prefix` as commands
+    pub fn new<M: ToString>(prefix: M) -> Self {
+        Self {
+            prefix: prefix.to_string(),
+            commands: BTreeMap::new(),
+            fallback: None,
+            greeting: None,
+            farewell: None,
+            idle_timeout: None,
+        }
+    }
+
+    /// Registers a command, `handler` gets the conversation state and everything
+    /// after the command name. If it returns a message it is sent as the reply.
+    ///
+    /// Unless a `help` command is registered, `help` lists every command with its description
+    pub fn command<N, D, F>(mut self, name: N, description: D, handler: F) -> Self
+    where
+        N: ToString,
+        D: ToString,
+        F: Fn(&mut S, &str) -> Option<String> + Send + Sync + 'static,
+    {
+        self.commands.insert(
+            name.to_string(),
+            Command {
+                description: description.to_string(),
+                handler: Box::new(handler),
+            },
+        );
+        self
+    }
+
+    /// Sets the handler for messages that aren't commands
+    pub fn fallback<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(&mut S, &str) -> Option<String> + Send + Sync + 'static,
+    {
+        self.fallback = Some(Box::new(handler));
+        self
+    }
+
+    /// Sets the hook called when a stranger connects, the returned message is sent to them
+    pub fn greeting<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&mut S) -> Option<String> + Send + Sync + 'static,
+    {
+        self.greeting = Some(Box::new(hook));
+        self
+    }
+
+    /// Sets the hook called when the chat ends
+    ///
+    /// The returned message is only sent if the bot is the one ending the chat,
+    /// since the stranger can't receive it otherwise
+    pub fn farewell<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&mut S) -> Option<String> + Send + Sync + 'static,
+    {
+        self.farewell = Some(Box::new(hook));
+        self
+    }
+
+    /// Disconnects from strangers that don't send a message or start typing for `timeout`
+    ///
+    /// Without it the [idle timeout](ChatSession::update_idle_timeout) of the session given to
+    /// [`Bot::run`] is kept, the farewell is then only sent if the session doesn't
+    /// [disconnect by itself](ChatSession::update_auto_disconnect)
+    pub fn idle_timeout(mut self, timeout: Duration) -> Self {
+        self.idle_timeout = Some(timeout);
+        self
+    }
+
+    /// Runs the bot on `session` until the chat ends, returning the conversation state
+    ///
+    /// # Errors
+    /// This function fails if:
+    /// - The omegle server cannot be reached
+    /// - The response from omegle was malformed or indicated an error
+    /// - Omegle reported an error during the chat, see [`OmegleLibError::OmegleError`]
+    pub async fn run(&self, session: &ChatSession) -> Result<S, OmegleLibError> {
+        // The clone shares the chat with `session` but gets its own idle settings
+        let mut session = session.clone();
+        if self.idle_timeout.is_some() {
+            session.update_idle_timeout(self.idle_timeout);
+            session.update_auto_disconnect(false);
+        }
+
+        let mut handler = BotHandler {
+            bot: self,
+            state: S::default(),
+            error: None,
+            idled_out: false,
+        };
+        session.run(&mut handler).await?;
+        if let Some(error) = handler.error {
+            return Err(error);
+        }
+
+        // Idle strangers already got the farewell before being disconnected
+        if !handler.idled_out {
+            if let Some(hook) = &self.farewell {
+                hook(&mut handler.state);
+            }
+        }
+        Ok(handler.state)
+    }
+
+    /// Gets the reply to a message, routing it to a command if it has the prefix
+    fn reply(&self, state: &mut S, message: &str) -> Option<String> {
+        match message.strip_prefix(&self.prefix) {
+            Some(command_line) => {
+                let (name, args) = command_line
+                    .split_once(char::is_whitespace)
+                    .unwrap_or((command_line, ""));
+                match self.commands.get(name) {
+                    Some(command) => (command.handler)(state, args.trim()),
+                    None if name == "help" => Some(self.help()),
+                    None => Some(format!("Unknown command '{name}', try {}help", self.prefix)),
+                }
+            }
+            None => self
+                .fallback
+                .as_ref()
+                .and_then(|handler| handler(state, message)),
+        }
+    }
+
+    /// Lists every command with its description
+    fn help(&self) -> String {
+        self.commands
+            .iter()
+            .map(|(name, command)| format!("{}{name} - {}", self.prefix, command.description))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Dispatch target holding the state of a single conversation
+struct BotHandler<'a, S> {
+    bot: &'a Bot<S>,
+    state: S,
+    /// The first action that failed, which stops the bot
+    error: Option<OmegleLibError>,
+    /// Whether the bot disconnected from an idle stranger
+    idled_out: bool,
+}
+
+impl<S> BotHandler<'_, S> {
+    async fn send(&mut self, session: &ChatSession, reply: Option<String>) {
+        if let Some(reply) = reply {
+            if let Err(error) = session.send_message(reply).await {
+                self.error = Some(error);
+            }
+        }
+    }
+
+    /// Says goodbye to an idle stranger and ends the chat
+    async fn leave(&mut self, session: &ChatSession) {
+        let farewell = self
+            .bot
+            .farewell
+            .as_ref()
+            .and_then(|hook| hook(&mut self.state));
+        self.send(session, farewell).await;
+        if self.error.is_none() {
+            if let Err(error) = session.disconnect().await {
+                self.error = Some(error);
+            }
+        }
+        self.idled_out = true;
+    }
+}
+
+#[async_trait]
+impl<S: Default + Send> EventHandler for BotHandler<'_, S> {
+    async fn on_connected(&mut self, session: &ChatSession) {
+        let reply = self
+            .bot
+            .greeting
+            .as_ref()
+            .and_then(|hook| hook(&mut self.state));
+        self.send(session, reply).await
+    }
+
+    async fn on_message(&mut self, session: &ChatSession, message: String) {
+        let reply = self.bot.reply(&mut self.state, &message);
+        self.send(session, reply).await
+    }
+
+    async fn on_error(&mut self, _session: &ChatSession, error: &OmegleLibError) {
+        // Polling errors are already returned by `ChatSession::run`
+        if let OmegleLibError::OmegleError(message) = error {
+            self.error
+                .get_or_insert(OmegleLibError::OmegleError(message.clone()));
+        }
+    }
+
+    async fn on_event(&mut self, session: &ChatSession, event: ChatEvent) {
+        if let ChatEvent::IdleTimeout {
+            disconnected: false,
+        } = event
+        {
+            self.leave(session).await
+        }
+    }
+
+    fn is_done(&self) -> bool {
+        self.error.is_some() || self.idled_out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::chat_session::tests::test_session;
+    use crate::transport::MemoryTransport;
+
+    use super::*;
+
+    fn test_bot() -> Bot<Vec<String>> {
+        Bot::<Vec<String>>::new("!")
+            .greeting(|_| Some(String::from("hello")))
+            .command("topic", String::from("Sets the topic"), |topics, args| {
+                topics.push(args.to_string());
+                Some(format!("topic is now {args}"))
+            })
+            .fallback(|_, message| Some(format!("you said {message}")))
+    }
+
+    fn sent_messages(transport: &MemoryTransport) -> Vec<String> {
+        transport
+            .requests()
+            .into_iter()
+            .filter(|request| request.url.ends_with("/send"))
+            .map(|request| request.form[1].1.clone())
+            .collect()
+    }
+
+    #[test]
+    fn routes_commands_and_keeps_state() {
+        let transport = Arc::new(MemoryTransport::new());
+        transport.push_response(
+            "/events",
+            r#"[["connected"], ["gotMessage", "!topic cats"], ["gotMessage", "hi"]]"#,
+        );
+        transport.push_response(
+            "/events",
+            r#"[["gotMessage", "!help"], ["gotMessage", "!nope"]]"#,
+        );
+        transport.push_response("/events", r#"[["strangerDisconnected"]]"#);
+        for _ in 0..5 {
+            transport.push_response("/send", "win");
+        }
+        let session = test_session(transport.clone());
+
+        let state = futures::executor::block_on(test_bot().run(&session)).unwrap();
+
+        assert_eq!(state, vec!["cats"]);
+        assert_eq!(
+            sent_messages(&transport),
+            vec![
+                "hello",
+                "topic is now cats",
+                "you said hi",
+                "!topic - Sets the topic",
+                "Unknown command 'nope', try !help"
+            ]
+        )
+    }
+
+    #[test]
+    fn fails_when_reply_could_not_be_sent() {
+        let transport = Arc::new(MemoryTransport::new());
+        transport.push_response("/events", r#"[["connected"]]"#);
+        transport.push_response("/send", "fail");
+        let session = test_session(transport);
+
+        let resp = futures::executor::block_on(test_bot().run(&session));
+        assert!(matches!(resp, Err(OmegleLibError::OmegleError(_))))
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn disconnects_idle_strangers() {
+        let transport = Arc::new(MemoryTransport::new());
+        transport.push_response("/events", r#"[["connected"]]"#);
+        transport.push_stall("/events");
+        transport.push_response("/send", "win");
+        transport.push_response("/send", "win");
+        transport.push_response("/disconnect", "win");
+        let session = test_session(transport.clone());
+
+        let bot = test_bot()
+            .farewell(|_| Some(String::from("bye")))
+            .idle_timeout(Duration::from_secs(30));
+        bot.run(&session).await.unwrap();
+
+        let requests = transport.requests();
+        assert_eq!(sent_messages(&transport), vec!["hello", "bye"]);
+        assert!(requests.last().unwrap().url.ends_with("/disconnect"))
+    }
+
+    #[test]
+    fn fails_when_omegle_reports_an_error() {
+        let transport = Arc::new(MemoryTransport::new());
+        transport.push_response("/events", r#"[["connected"], ["error", "oops"]]"#);
+        transport.push_response("/send", "win");
+        let session = test_session(transport);
+
+        let resp = futures::executor::block_on(test_bot().run(&session));
+        assert!(matches!(resp, Err(OmegleLibError::OmegleError(message)) if message == "oops"))
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn keeps_idle_settings_of_the_session() {
+        let transport = Arc::new(MemoryTransport::new());
+        transport.push_response("/events", r#"[["connected"]]"#);
+        transport.push_stall("/events");
+        transport.push_response("/send", "win");
+        transport.push_response("/disconnect", "win");
+        let mut session = test_session(transport.clone());
+        session.update_idle_timeout(Some(Duration::from_secs(30)));
+        session.update_auto_disconnect(true);
+
+        let bot = test_bot().farewell(|_| Some(String::from("bye")));
+        bot.run(&session).await.unwrap();
+
+        // The session disconnected by itself, so the farewell could not be sent
+        let requests = transport.requests();
+        assert_eq!(sent_messages(&transport), vec!["hello"]);
+        assert!(requests.last().unwrap().url.ends_with("/disconnect"))
+    }
+}
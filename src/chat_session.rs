@@ -1,50 +1,244 @@
-use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
+use tokio::time::Instant;
+
+use crate::ban::BanRecord;
+use crate::event_handler::{dispatch, EventHandler};
+use crate::filter::{Direction, FilterPipeline, Verdict};
+use crate::spam::{SpamClassifier, SpamScore, Timeline};
+use crate::transport::{post_form_json, Transport};
+use crate::types::rtc::{IceCandidate, RtcSessionDescription};
 use crate::types::{chat_event::ChatEvent, error::OmegleLibError};
-use crate::types::{chat_server::ChatServer, client_id::ClientID};
+use crate::types::{chat_server::ChatServer, client_id::ClientID, endpoints::Endpoints};
 
-use reqwest::Client;
-use vec1::Vec1;
+use vec1::{vec1, Vec1};
 
 static OMEGLE_SUCCESS_RESP: &str = "win";
 
+/// Turns the text omegle responds with after an action into a [`Result`]
+pub(crate) fn parse_action_response(resp: String) -> Result<(), OmegleLibError> {
+    if resp == OMEGLE_SUCCESS_RESP {
+        Ok(())
+    } else {
+        Err(OmegleLibError::OmegleError(resp))
+    }
+}
+
+/// Takes the pending events out of a session, if there are any
+pub(crate) fn take_pending_events(
+    pending_events: &Mutex<Vec<ChatEvent>>,
+) -> Option<Vec1<ChatEvent>> {
+    let events = std::mem::take(&mut *pending_events.lock().expect("lock is not poisoned"));
+    Vec1::try_from_vec(events).ok()
+}
+
+/// Runs `request`, failing with [`OmegleLibError::Timeout`] if it takes longer than `timeout`
+pub(crate) async fn with_timeout<T>(
+    timeout: Option<Duration>,
+    request: impl Future<Output = Result<T, OmegleLibError>>,
+) -> Result<T, OmegleLibError> {
+    match timeout {
+        Some(timeout) => tokio::time::timeout(timeout, request)
+            .await
+            .map_err(|_| OmegleLibError::Timeout(timeout))?,
+        None => request.await,
+    }
+}
+
 /// Struct representing a single ongoing chat session
 #[derive(Debug, Clone)]
 pub struct ChatSession {
     pub(crate) client_id: ClientID,
-    pub(crate) client: Client,
+    pub(crate) transport: Arc<dyn Transport>,
     pub(crate) server: ChatServer,
+    pub(crate) endpoints: Arc<Endpoints>,
+    /// Events that came with the start response and haven't been handed out yet
+    pub(crate) pending_events: Arc<Mutex<Vec<ChatEvent>>>,
+    pub(crate) idle_timeout: Option<Duration>,
+    pub(crate) auto_disconnect: bool,
+    pub(crate) long_poll_timeout: Option<Duration>,
+    pub(crate) action_timeout: Option<Duration>,
+    /// When the stranger last connected, typed or sent a message, `None` while not chatting
+    pub(crate) last_activity: Arc<Mutex<Option<Instant>>>,
+    pub(crate) filter: Option<Arc<FilterPipeline>>,
+    pub(crate) spam_classifier: Option<Arc<SpamClassifier>>,
+    pub(crate) auto_skip: bool,
+    /// What the stranger did so far, kept for the spam classifier
+    pub(crate) timeline: Arc<Mutex<Timeline>>,
+    /// Where bans are reported, shared with the [`Omegle`](crate::omegle::Omegle) that started the chat
+    pub(crate) ban_record: Arc<BanRecord>,
 }
 
 impl ChatSession {
+    pub(crate) fn new(
+        client_id: ClientID,
+        transport: Arc<dyn Transport>,
+        server: ChatServer,
+        endpoints: Arc<Endpoints>,
+        initial_events: Vec<ChatEvent>,
+    ) -> Self {
+        Self {
+            client_id,
+            transport,
+            server,
+            endpoints,
+            pending_events: Arc::new(Mutex::new(initial_events)),
+            idle_timeout: None,
+            auto_disconnect: false,
+            long_poll_timeout: None,
+            action_timeout: None,
+            last_activity: Arc::new(Mutex::new(None)),
+            filter: None,
+            spam_classifier: None,
+            auto_skip: false,
+            timeline: Arc::new(Mutex::new(Timeline::new())),
+            ban_record: Arc::new(BanRecord::default()),
+        }
+    }
+
+    /// Updates how long the stranger can go without sending a message or typing
+    ///
+    /// Once the stranger has been idle for that long, [`ChatSession::get_events`] returns
+    /// a [`ChatEvent::IdleTimeout`] instead of waiting for the server. The timer starts
+    /// when the stranger connects. `None` (the default) waits forever.
+    ///
+    /// # Notes:
+    /// Waiting with a timeout requires running inside a tokio runtime
+    pub fn update_idle_timeout(&mut self, idle_timeout: Option<Duration>) {
+        self.idle_timeout = idle_timeout
+    }
+
+    /// Gets how long the stranger can be idle, if there is a limit
+    pub fn get_idle_timeout(&self) -> Option<Duration> {
+        self.idle_timeout
+    }
+
+    /// Updates whether the chat is disconnected automatically when the stranger is idle for too long
+    pub fn update_auto_disconnect(&mut self, auto_disconnect: bool) {
+        self.auto_disconnect = auto_disconnect
+    }
+
+    /// Gets whether the chat is disconnected automatically when the stranger is idle for too long
+    pub fn is_auto_disconnect(&self) -> bool {
+        self.auto_disconnect
+    }
+
+    /// Updates how long a single request for events can take before failing with
+    /// [`OmegleLibError::Timeout`]
+    ///
+    /// Omegle holds these requests open until something happens, so this should be
+    /// generous. A timed out request can simply be retried. `None` (the default) waits forever.
+    ///
+    /// # Notes:
+    /// Waiting with a timeout requires running inside a tokio runtime
+    pub fn update_long_poll_timeout(&mut self, long_poll_timeout: Option<Duration>) {
+        self.long_poll_timeout = long_poll_timeout
+    }
+
+    /// Gets how long a single request for events can take, if there is a limit
+    pub fn get_long_poll_timeout(&self) -> Option<Duration> {
+        self.long_poll_timeout
+    }
+
+    /// Updates how long sending messages, typing indicators, signaling data and
+    /// disconnects can take before failing with [`OmegleLibError::Timeout`]
+    ///
+    /// `None` (the default) waits forever.
+    ///
+    /// # Notes:
+    /// Waiting with a timeout requires running inside a tokio runtime
+    pub fn update_action_timeout(&mut self, action_timeout: Option<Duration>) {
+        self.action_timeout = action_timeout
+    }
+
+    /// Gets how long actions can take, if there is a limit
+    pub fn get_action_timeout(&self) -> Option<Duration> {
+        self.action_timeout
+    }
+
+    /// Updates the filter messages go through before being sent or handed out
+    ///
+    /// Every rule that matches is reported as a [`ChatEvent::Filtered`], for sent
+    /// messages it is returned by the next call to [`ChatSession::get_events`].
+    /// `None` (the default) lets every message through.
+    pub fn update_filter(&mut self, filter: Option<FilterPipeline>) {
+        self.filter = filter.map(Arc::new)
+    }
+
+    /// Gets the filter messages go through, if there is one
+    pub fn get_filter(&self) -> Option<&FilterPipeline> {
+        self.filter.as_deref()
+    }
+
+    /// Updates the classifier checking whether the stranger is a spam bot
+    ///
+    /// The stranger is checked after every message they send, the first time they
+    /// are considered spam [`ChatSession::get_events`] returns a
+    /// [`ChatEvent::SpamDetected`] right after that message. `None` (the default)
+    /// doesn't check strangers.
+    pub fn update_spam_classifier(&mut self, spam_classifier: Option<SpamClassifier>) {
+        self.spam_classifier = spam_classifier.map(Arc::new)
+    }
+
+    /// Gets the classifier checking whether the stranger is a spam bot, if there is one
+    pub fn get_spam_classifier(&self) -> Option<&SpamClassifier> {
+        self.spam_classifier.as_deref()
+    }
+
+    /// Updates whether the chat is disconnected automatically when the stranger is spam
+    pub fn update_auto_skip(&mut self, auto_skip: bool) {
+        self.auto_skip = auto_skip
+    }
+
+    /// Gets whether the chat is disconnected automatically when the stranger is spam
+    pub fn is_auto_skip(&self) -> bool {
+        self.auto_skip
+    }
+
+    /// Scores the stranger with the spam classifier, if there is one
+    pub fn get_spam_score(&self) -> Option<SpamScore> {
+        let timeline = self.timeline.lock().expect("lock is not poisoned");
+        self.spam_classifier
+            .as_ref()
+            .map(|classifier| classifier.score(&timeline))
+    }
+
     /// Sends a message to the other party.
     ///
+    /// If a [filter](ChatSession::update_filter) is set the message goes through it
+    /// first and may be redacted, dropped, or dropped and the chat disconnected
+    ///
     /// # Errors
     /// This function fails if:
     /// - The omegle server cannot be reached
     /// - The response from omegle indicated an error
+    /// - The message was dropped by the filter, see [`OmegleLibError::Filtered`]
     pub async fn send_message<M: ToString>(&self, message: M) -> Result<(), OmegleLibError> {
-        let chat_server_string = String::from(self.server);
-        let client_id_string = String::from(self.client_id);
-        let message_string = message.to_string();
-
-        let mut form = HashMap::new();
-        form.insert("id", client_id_string);
-        form.insert("msg", message_string);
-        let resp = self
-            .client
-            .post(format!("http://{chat_server_string}.omegle.com/send"))
-            .form(&form)
-            .send()
-            .await?
-            .text()
-            .await?;
+        let mut message_string = message.to_string();
+
+        if let Some(filter) = &self.filter {
+            let outcome = filter.apply(Direction::Outgoing, &message_string);
+            let stopped_by = outcome.matches.last().cloned();
+            self.pending_events
+                .lock()
+                .expect("lock is not poisoned")
+                .extend(outcome.matches.into_iter().map(ChatEvent::Filtered));
 
-        if resp == OMEGLE_SUCCESS_RESP {
-            Ok(())
-        } else {
-            Err(OmegleLibError::OmegleError(resp))
+            let blocked =
+                || OmegleLibError::Filtered(stopped_by.expect("blocked messages matched a rule"));
+            match outcome.verdict {
+                Verdict::Pass(filtered) => message_string = filtered,
+                Verdict::Drop => return Err(blocked()),
+                Verdict::Disconnect => {
+                    self.disconnect().await?;
+                    return Err(blocked());
+                }
+            }
         }
+
+        self.post_action("send", &[("msg", &message_string)]).await
     }
 
     /// Sends a typing indicator to the server
@@ -59,25 +253,7 @@ impl ChatSession {
     /// - The omegle server cannot be reached
     /// - The response from omegle indicated an error
     pub async fn start_typing(&self) -> Result<(), OmegleLibError> {
-        let chat_server_string = String::from(self.server);
-        let client_id_string = String::from(self.client_id);
-
-        let mut form = HashMap::new();
-        form.insert("id", client_id_string);
-        let resp = self
-            .client
-            .post(format!("http://{chat_server_string}.omegle.com/typing"))
-            .form(&form)
-            .send()
-            .await?
-            .text()
-            .await?;
-
-        if resp == OMEGLE_SUCCESS_RESP {
-            Ok(())
-        } else {
-            Err(OmegleLibError::OmegleError(resp))
-        }
+        self.post_action("typing", &[]).await
     }
 
     /// Sends a stopped typing indicator to the server
@@ -93,27 +269,7 @@ impl ChatSession {
     /// - The omegle server cannot be reached
     /// - The response from omegle indicated an error
     pub async fn stop_typing(&self) -> Result<(), OmegleLibError> {
-        let chat_server_string = String::from(self.server);
-        let client_id_string = String::from(self.client_id);
-
-        let mut form = HashMap::new();
-        form.insert("id", client_id_string);
-        let resp = self
-            .client
-            .post(format!(
-                "http://{chat_server_string}.omegle.com/stoppedtyping"
-            ))
-            .form(&form)
-            .send()
-            .await?
-            .text()
-            .await?;
-
-        if resp == OMEGLE_SUCCESS_RESP {
-            Ok(())
-        } else {
-            Err(OmegleLibError::OmegleError(resp))
-        }
+        self.post_action("stoppedtyping", &[]).await
     }
 
     /// Gets a list of [`ChatEvent`] from the server.
@@ -123,27 +279,221 @@ impl ChatSession {
     /// You should run this on a seperate executor and relaunch it as soon as
     /// the previous call returns
     ///
+    /// Events omegle sent along with the start of the chat are returned
+    /// by the first call, without polling the server. Responses without any
+    /// events are polled again
+    ///
+    /// If an [idle timeout](ChatSession::update_idle_timeout) is set and the stranger
+    /// stays idle for too long, a [`ChatEvent::IdleTimeout`] is returned instead
+    ///
+    /// If a [filter](ChatSession::update_filter) is set, messages from the stranger go
+    /// through it and every rule that matched is reported by a [`ChatEvent::Filtered`]
+    /// placed before the message, or in its place if it was dropped
+    ///
+    /// If a [spam classifier](ChatSession::update_spam_classifier) is set, it checks
+    /// the stranger before any filtering happens
+    ///
     /// # Errors
     /// This function fails if:
     /// - The omegle server cannot be reached
     /// - The response from omegle was malformed
     /// - The function was called after the chat ended
+    /// - The chat had to be disconnected automatically but omegle responded with an error
+    ///
+    /// # Notes:
+    /// When omegle bans the chat the [`Omegle`](crate::omegle::Omegle) that started it
+    /// records the ban and the [`Transport`] is told through [`Transport::report_ban`]
     pub async fn get_events(&self) -> Result<Vec1<ChatEvent>, OmegleLibError> {
-        let chat_server_string = String::from(self.server);
+        let events = match take_pending_events(&self.pending_events) {
+            Some(pending_events) => pending_events,
+            None => {
+                let idle_deadline = self
+                    .idle_timeout
+                    .zip(*self.last_activity.lock().expect("lock is not poisoned"))
+                    .map(|(idle_timeout, last_activity)| last_activity + idle_timeout);
+                match idle_deadline {
+                    Some(idle_deadline) => {
+                        match tokio::time::timeout_at(idle_deadline, self.poll_events()).await {
+                            Ok(events) => events?,
+                            Err(_) => return self.idle_timed_out().await,
+                        }
+                    }
+                    None => self.poll_events().await?,
+                }
+            }
+        };
+        // Recorded first, skipping a spam bot drops the rest of the events
+        if events.contains(&ChatEvent::Banned) {
+            self.ban_record.record(Instant::now());
+            self.transport.report_ban();
+        }
+        let events = self.detect_spam(events).await?;
+        let events = self.filter_incoming(events).await?;
+        self.track_activity(&events);
+        Ok(events)
+    }
+
+    /// Records `events` for the spam classifier and reports the stranger once they are spam
+    async fn detect_spam(
+        &self,
+        events: Vec1<ChatEvent>,
+    ) -> Result<Vec1<ChatEvent>, OmegleLibError> {
+        let Some(classifier) = &self.spam_classifier else {
+            return Ok(events);
+        };
+
+        let mut checked = Vec::with_capacity(events.len());
+        for event in events {
+            let detected = {
+                let mut timeline = self.timeline.lock().expect("lock is not poisoned");
+                timeline.record_at(&event, Instant::now());
+                match event {
+                    ChatEvent::Message(_) if !timeline.flagged => {
+                        let score = classifier.score(&timeline);
+                        timeline.flagged = score.is_spam;
+                        Some(score).filter(|score| score.is_spam)
+                    }
+                    _ => None,
+                }
+            };
+            checked.push(event);
+
+            if let Some(score) = detected {
+                if self.auto_skip {
+                    self.disconnect().await?;
+                }
+                checked.push(ChatEvent::SpamDetected {
+                    score,
+                    disconnected: self.auto_skip,
+                });
+                if self.auto_skip {
+                    break;
+                }
+            }
+        }
+        self.timeline
+            .lock()
+            .expect("lock is not poisoned")
+            .end_batch();
+
+        Ok(Vec1::try_from_vec(checked).expect("every event is kept"))
+    }
+
+    /// Runs the messages among `events` through the filter, if there is one
+    async fn filter_incoming(
+        &self,
+        events: Vec1<ChatEvent>,
+    ) -> Result<Vec1<ChatEvent>, OmegleLibError> {
+        let Some(filter) = &self.filter else {
+            return Ok(events);
+        };
+
+        let mut filtered = Vec::with_capacity(events.len());
+        for event in events {
+            let ChatEvent::Message(message) = event else {
+                filtered.push(event);
+                continue;
+            };
+
+            let outcome = filter.apply(Direction::Incoming, &message);
+            filtered.extend(outcome.matches.into_iter().map(ChatEvent::Filtered));
+            match outcome.verdict {
+                Verdict::Pass(message) => filtered.push(ChatEvent::Message(message)),
+                Verdict::Drop => {}
+                Verdict::Disconnect => {
+                    self.disconnect().await?;
+                    break;
+                }
+            }
+        }
+
+        Ok(Vec1::try_from_vec(filtered)
+            .expect("every event is kept or replaced by at least one match"))
+    }
+
+    /// Long-polls the server for new events, bounded by the long-poll timeout
+    async fn poll_events(&self) -> Result<Vec1<ChatEvent>, OmegleLibError> {
         let client_id_string = String::from(self.client_id);
 
-        let mut form = HashMap::new();
-        form.insert("id", client_id_string);
-        let resp = self
-            .client
-            .post(format!("http://{chat_server_string}.omegle.com/events"))
-            .form(&form)
-            .send()
-            .await?
-            .json::<Vec1<ChatEvent>>()
+        let form = [("id", client_id_string.as_str())];
+        loop {
+            let events = with_timeout(
+                self.long_poll_timeout,
+                post_form_json::<Vec<ChatEvent>>(
+                    self.transport.as_ref(),
+                    &self.endpoints.chat_url(self.server, "events"),
+                    &form,
+                ),
+            )
             .await?;
+            // Long-polls that ended without events are simply sent again
+            if let Ok(events) = Vec1::try_from_vec(events) {
+                return Ok(events);
+            }
+        }
+    }
 
-        Ok(resp)
+    /// Restarts the idle timer when the stranger did something
+    fn track_activity(&self, events: &Vec1<ChatEvent>) {
+        let mut last_activity = self.last_activity.lock().expect("lock is not poisoned");
+        for event in events {
+            match event {
+                ChatEvent::Connected | ChatEvent::StartedTyping | ChatEvent::Message(_) => {
+                    *last_activity = Some(Instant::now())
+                }
+                event if event.ends_chat() => *last_activity = None,
+                _ => {}
+            }
+        }
+    }
+
+    /// Builds the events returned once the stranger was idle for too long
+    async fn idle_timed_out(&self) -> Result<Vec1<ChatEvent>, OmegleLibError> {
+        *self.last_activity.lock().expect("lock is not poisoned") = None;
+        if self.auto_disconnect {
+            self.disconnect().await?;
+        }
+        Ok(vec1![ChatEvent::IdleTimeout {
+            disconnected: self.auto_disconnect
+        }])
+    }
+
+    /// Polls events and dispatches them to `handler` until the chat ends
+    ///
+    /// The chat ends once an event that [ends the chat](ChatEvent::ends_chat) was
+    /// dispatched, or once the handler [is done](EventHandler::is_done). If events
+    /// could not be fetched the error is passed to [`EventHandler::on_error`] before
+    /// being returned.
+    ///
+    /// # Errors
+    /// This function fails if:
+    /// - The omegle server cannot be reached
+    /// - The response from omegle was malformed
+    pub async fn run<H: EventHandler + ?Sized>(
+        &self,
+        handler: &mut H,
+    ) -> Result<(), OmegleLibError> {
+        loop {
+            let events = match self.get_events().await {
+                Ok(events) => events,
+                Err(error) => {
+                    handler.on_error(self, &error).await;
+                    return Err(error);
+                }
+            };
+
+            let mut chat_ended = false;
+            for event in events {
+                chat_ended |= event.ends_chat();
+                dispatch(handler, self, event).await;
+                if handler.is_done() {
+                    return Ok(());
+                }
+            }
+            if chat_ended {
+                return Ok(());
+            }
+        }
     }
 
     /// Sends a disconnect request to the server
@@ -153,24 +503,367 @@ impl ChatSession {
     /// - The omegle server cannot be reached
     /// - The response from omegle indicated an error
     pub async fn disconnect(&self) -> Result<(), OmegleLibError> {
-        let chat_server_string = String::from(self.server);
+        self.post_action("disconnect", &[]).await
+    }
+
+    /// Sends our WebRTC session description (offer or answer) to the other party
+    ///
+    /// Only meaningful in chats started in a video [`ChatMode`](crate::types::chat_mode::ChatMode), see
+    /// [`Omegle::update_mode`](crate::omegle::Omegle::update_mode)
+    ///
+    /// # Errors
+    /// This function fails if:
+    /// - The omegle server cannot be reached
+    /// - The response from omegle indicated an error
+    pub async fn send_peer_description(
+        &self,
+        description: &RtcSessionDescription,
+    ) -> Result<(), OmegleLibError> {
+        let description_string = serde_json::to_string(description)?;
+        self.post_action("rtcpeerdescription", &[("desc", &description_string)])
+            .await
+    }
+
+    /// Sends one of our WebRTC ICE candidates to the other party
+    ///
+    /// # Errors
+    /// This function fails if:
+    /// - The omegle server cannot be reached
+    /// - The response from omegle indicated an error
+    pub async fn send_ice_candidate(&self, candidate: &IceCandidate) -> Result<(), OmegleLibError> {
+        let candidate_string = serde_json::to_string(candidate)?;
+        self.post_action("icecandidate", &[("candidate", &candidate_string)])
+            .await
+    }
+
+    /// Posts the client id and `fields` to an action endpoint, bounded by the action timeout
+    async fn post_action(
+        &self,
+        endpoint: &str,
+        fields: &[(&str, &str)],
+    ) -> Result<(), OmegleLibError> {
         let client_id_string = String::from(self.client_id);
 
-        let mut form = HashMap::new();
-        form.insert("id", client_id_string);
-        let resp = self
-            .client
-            .post(format!("http://{chat_server_string}.omegle.com/disconnect"))
-            .form(&form)
-            .send()
-            .await?
-            .text()
-            .await?;
+        let mut form = vec![("id", client_id_string.as_str())];
+        form.extend_from_slice(fields);
+        let resp = with_timeout(
+            self.action_timeout,
+            self.transport
+                .post_form(&self.endpoints.chat_url(self.server, endpoint), &form),
+        )
+        .await?;
 
-        if resp == OMEGLE_SUCCESS_RESP {
-            Ok(())
-        } else {
-            Err(OmegleLibError::OmegleError(resp))
-        }
+        parse_action_response(resp)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use futures::executor::block_on;
+
+    use crate::filter::{FilterAction, FilterMatch, FilterRule};
+    use crate::spam::SpamSignal;
+    use crate::transport::MemoryTransport;
+    use crate::types::client_id::ServerType;
+    use crate::types::rtc::SdpType;
+
+    use super::*;
+
+    pub(crate) fn test_session(transport: Arc<MemoryTransport>) -> ChatSession {
+        ChatSession::new(
+            ClientID {
+                server_type: ServerType::Central,
+                server_id: 1,
+                user_id: ['a'; 30],
+            },
+            transport,
+            ChatServer { id_number: 1 },
+            Arc::new(Endpoints::default()),
+            Vec::new(),
+        )
+    }
+
+    #[test]
+    fn send_message_posts_id_and_message() {
+        let transport = Arc::new(MemoryTransport::new());
+        transport.push_response("/send", "win");
+        let session = test_session(transport.clone());
+
+        block_on(session.send_message("hello")).unwrap();
+
+        let requests = transport.requests();
+        assert_eq!(requests[0].url, "http://front1.omegle.com/send");
+        assert_eq!(
+            requests[0].form,
+            vec![
+                (
+                    String::from("id"),
+                    String::from("central1:aaaaaaaaaaaaaaaaaaaaaaaaaaaaaa")
+                ),
+                (String::from("msg"), String::from("hello"))
+            ]
+        )
+    }
+
+    #[test]
+    fn action_errors_on_unexpected_response() {
+        let transport = Arc::new(MemoryTransport::new());
+        transport.push_response("/typing", "fail");
+        let session = test_session(transport);
+
+        let resp = block_on(session.start_typing());
+        assert!(matches!(resp, Err(OmegleLibError::OmegleError(msg)) if msg == "fail"))
+    }
+
+    #[test]
+    fn send_peer_description_posts_json() {
+        let transport = Arc::new(MemoryTransport::new());
+        transport.push_response("/rtcpeerdescription", "win");
+        let session = test_session(transport.clone());
+
+        block_on(session.send_peer_description(&RtcSessionDescription {
+            sdp_type: SdpType::Answer,
+            sdp: String::from("v=0"),
+        }))
+        .unwrap();
+
+        let requests = transport.requests();
+        assert_eq!(
+            requests[0].url,
+            "http://front1.omegle.com/rtcpeerdescription"
+        );
+        assert_eq!(
+            requests[0].form[1],
+            (
+                String::from("desc"),
+                String::from(r#"{"type":"answer","sdp":"v=0"}"#)
+            )
+        )
+    }
+
+    #[test]
+    fn get_events_replays_initial_events_first() {
+        let transport = Arc::new(MemoryTransport::new());
+        transport.push_response("/events", r#"[["connected"]]"#);
+        let session = test_session(transport.clone());
+        *session.pending_events.lock().unwrap() = vec![ChatEvent::Waiting];
+
+        let first = block_on(session.get_events()).unwrap();
+        assert_eq!(first.into_vec(), vec![ChatEvent::Waiting]);
+        assert!(transport.requests().is_empty());
+
+        let second = block_on(session.get_events()).unwrap();
+        assert_eq!(second.into_vec(), vec![ChatEvent::Connected])
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn get_events_times_out_idle_strangers() {
+        let transport = Arc::new(MemoryTransport::new());
+        transport.push_response("/events", r#"[["connected"]]"#);
+        transport.push_stall("/events");
+        transport.push_response("/disconnect", "win");
+        let mut session = test_session(transport.clone());
+        session.update_idle_timeout(Some(Duration::from_secs(30)));
+        session.update_auto_disconnect(true);
+
+        session.get_events().await.unwrap();
+        let events = session.get_events().await.unwrap();
+
+        assert_eq!(
+            events.into_vec(),
+            vec![ChatEvent::IdleTimeout { disconnected: true }]
+        );
+        assert!(transport.requests()[2].url.ends_with("/disconnect"))
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn actions_fail_after_action_timeout() {
+        let transport = Arc::new(MemoryTransport::new());
+        transport.push_stall("/send");
+        let mut session = test_session(transport);
+        session.update_action_timeout(Some(Duration::from_secs(5)));
+
+        let resp = session.send_message("hi").await;
+        assert!(matches!(resp, Err(OmegleLibError::Timeout(timeout)) if timeout.as_secs() == 5))
+    }
+
+    #[tokio::test]
+    async fn get_events_polls_again_after_empty_response() {
+        let transport = Arc::new(MemoryTransport::new());
+        transport.push_response("/events", "[]");
+        transport.push_response("/events", r#"[["connected"]]"#);
+        let session = test_session(transport.clone());
+
+        assert_eq!(
+            session.get_events().await.unwrap().into_vec(),
+            vec![ChatEvent::Connected]
+        );
+        assert_eq!(transport.requests().len(), 2)
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn get_events_fails_after_long_poll_timeout() {
+        let transport = Arc::new(MemoryTransport::new());
+        transport.push_stall("/events");
+        let mut session = test_session(transport);
+        session.update_long_poll_timeout(Some(Duration::from_secs(60)));
+        session.update_action_timeout(Some(Duration::from_secs(5)));
+
+        let resp = session.get_events().await;
+        assert!(matches!(resp, Err(OmegleLibError::Timeout(timeout)) if timeout.as_secs() == 60))
+    }
+
+    #[test]
+    fn get_events_does_not_time_out_before_connecting() {
+        let transport = Arc::new(MemoryTransport::new());
+        transport.push_response("/events", r#"[["waiting"]]"#);
+        let mut session = test_session(transport);
+        session.update_idle_timeout(Some(Duration::from_secs(30)));
+
+        // No tokio runtime is needed since no timer is started
+        let events = block_on(session.get_events()).unwrap();
+        assert_eq!(events.into_vec(), vec![ChatEvent::Waiting])
+    }
+
+    #[test]
+    fn get_events_parses_response() {
+        let transport = Arc::new(MemoryTransport::new());
+        transport.push_response("/events", r#"[["connected"], ["gotMessage", "hi"]]"#);
+        let session = test_session(transport);
+
+        let events = block_on(session.get_events()).unwrap();
+        assert_eq!(
+            events.into_vec(),
+            vec![ChatEvent::Connected, ChatEvent::Message(String::from("hi"))]
+        )
+    }
+
+    fn test_filter() -> FilterPipeline {
+        FilterPipeline::new()
+            .rule(FilterRule::phone_numbers(FilterAction::Redact))
+            .rule(FilterRule::keywords("spam", ["subscribe"], FilterAction::Drop).unwrap())
+            .rule(FilterRule::keywords("slurs", ["badword"], FilterAction::Disconnect).unwrap())
+    }
+
+    fn filter_match(rule: &str, action: FilterAction, direction: Direction) -> ChatEvent {
+        ChatEvent::Filtered(FilterMatch {
+            rule: String::from(rule),
+            action,
+            direction,
+        })
+    }
+
+    #[test]
+    fn send_message_applies_filter() {
+        let transport = Arc::new(MemoryTransport::new());
+        transport.push_response("/send", "win");
+        transport.push_response("/disconnect", "win");
+        let mut session = test_session(transport.clone());
+        session.update_filter(Some(test_filter()));
+
+        block_on(session.send_message("text me 5551234567")).unwrap();
+        let dropped = block_on(session.send_message("please subscribe"));
+        let disconnected = block_on(session.send_message("badword"));
+
+        assert!(matches!(dropped, Err(OmegleLibError::Filtered(m)) if m.rule == "spam"));
+        assert!(matches!(disconnected, Err(OmegleLibError::Filtered(m)) if m.rule == "slurs"));
+        let requests = transport.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].form[1].1, "text me ***");
+        assert!(requests[1].url.ends_with("/disconnect"));
+
+        let events = block_on(session.get_events()).unwrap();
+        assert_eq!(
+            events.into_vec(),
+            vec![
+                filter_match("phone numbers", FilterAction::Redact, Direction::Outgoing),
+                filter_match("spam", FilterAction::Drop, Direction::Outgoing),
+                filter_match("slurs", FilterAction::Disconnect, Direction::Outgoing),
+            ]
+        )
+    }
+
+    #[test]
+    fn get_events_applies_filter() {
+        let transport = Arc::new(MemoryTransport::new());
+        transport.push_response(
+            "/events",
+            r#"[["gotMessage", "subscribe now"], ["gotMessage", "call 5551234567"], ["gotMessage", "badword"], ["typing"]]"#,
+        );
+        transport.push_response("/disconnect", "win");
+        let mut session = test_session(transport.clone());
+        session.update_filter(Some(test_filter()));
+
+        let events = block_on(session.get_events()).unwrap().into_vec();
+
+        assert_eq!(
+            events,
+            vec![
+                filter_match("spam", FilterAction::Drop, Direction::Incoming),
+                filter_match("phone numbers", FilterAction::Redact, Direction::Incoming),
+                ChatEvent::Message(String::from("call ***")),
+                filter_match("slurs", FilterAction::Disconnect, Direction::Incoming),
+            ]
+        );
+        assert!(events.last().unwrap().ends_chat());
+        assert!(transport.requests()[1].url.ends_with("/disconnect"))
+    }
+
+    #[test]
+    fn get_events_skips_spam_bots() {
+        let transport = Arc::new(MemoryTransport::new());
+        transport.push_response(
+            "/events",
+            r#"[["connected"], ["gotMessage", "add me on snap"], ["gotMessage", "pls"]]"#,
+        );
+        transport.push_response("/disconnect", "win");
+        let mut session = test_session(transport.clone());
+        session.update_spam_classifier(Some(SpamClassifier::new()));
+        session.update_auto_skip(true);
+
+        let events = block_on(session.get_events()).unwrap().into_vec();
+
+        assert_eq!(events.len(), 3);
+        assert!(matches!(
+            &events[2],
+            ChatEvent::SpamDetected { score, disconnected: true } if score.is_spam
+        ));
+        assert!(events[2].ends_chat());
+        assert!(transport.requests()[1].url.ends_with("/disconnect"))
+    }
+
+    #[test]
+    fn messages_polled_with_the_connection_are_not_too_fast() {
+        let transport = Arc::new(MemoryTransport::new());
+        transport.push_response("/events", r#"[["connected"], ["gotMessage", "hi"]]"#);
+        transport.push_response("/events", r#"[["gotMessage", "asl?"]]"#);
+        let mut session = test_session(transport);
+        session.update_spam_classifier(Some(SpamClassifier::new()));
+
+        block_on(session.get_events()).unwrap();
+        block_on(session.get_events()).unwrap();
+
+        assert_eq!(
+            session.get_spam_score().unwrap().signals,
+            vec![SpamSignal::NeverTyped]
+        )
+    }
+
+    #[test]
+    fn records_bans_dropped_by_auto_skip() {
+        let transport = Arc::new(MemoryTransport::new());
+        transport.push_response(
+            "/events",
+            r#"[["connected"], ["gotMessage", "add me on snap"], ["antinudeBanned"]]"#,
+        );
+        transport.push_response("/disconnect", "win");
+        let mut session = test_session(transport);
+        session.update_spam_classifier(Some(SpamClassifier::new()));
+        session.update_auto_skip(true);
+
+        let events = block_on(session.get_events()).unwrap();
+
+        assert!(!events.contains(&ChatEvent::Banned));
+        assert!(session.ban_record.get_banned_at().is_some())
     }
 }
@@ -0,0 +1,268 @@
+//! Persisted user profiles, requires the `config` feature
+//!
+//! A [`Config`] holds the interests, [`LangCode`], [`RandID`] and [`Endpoints`]
+//! of a user and is stored as TOML:
+//! ```toml
+//! lang = "fr"
+//! interests = ["knitting", "music"]
+//! rand_id = "ABCDEFGH"
+//!
+//! [endpoints]
+//! status_url = "https://omegle.com/status"
+//! domain = "omegle.com"
+//! ```
+//! Every key is optional. When there is no `rand_id` one is generated the first
+//! time an [`Omegle`] is built and saved back to the file, so the user keeps the
+//! same random ID across runs like the website does with its cookie.
+
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::omegle::Omegle;
+use crate::status::OmegleStatus;
+use crate::transport::{ReqwestTransport, Transport};
+use crate::types::{endpoints::Endpoints, error::OmegleLibError, lang::LangCode, rand_id::RandID};
+
+/// A user profile, see the [module documentation](self) for the file format
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// The language to chat in
+    pub lang: LangCode,
+    /// The interests to be matched on, invalid ones are skipped
+    pub interests: Vec<String>,
+    /// The random ID to start chats with, generated when missing
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rand_id: Option<RandID>,
+    /// Where the omegle servers are reached
+    pub endpoints: Endpoints,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            lang: LangCode::English,
+            interests: Vec::new(),
+            rand_id: None,
+            endpoints: Endpoints::default(),
+        }
+    }
+}
+
+impl Config {
+    /// Parses a config from TOML
+    ///
+    /// # Errors
+    /// This function fails if the text is not valid TOML or has invalid values
+    /// (Ex: an unknown language or a random ID that doesn't follow convention)
+    pub fn parse(text: &str) -> Result<Self, OmegleLibError> {
+        Ok(toml::from_str(text)?)
+    }
+
+    /// Turns the config into TOML
+    ///
+    /// # Errors
+    /// This function fails if the config cannot be represented as TOML
+    pub fn to_toml(&self) -> Result<String, OmegleLibError> {
+        Ok(toml::to_string(self)?)
+    }
+
+    /// Reads a config from a file, a missing file gives the default config
+    ///
+    /// # Errors
+    /// This function fails if:
+    /// - The file exists but cannot be read
+    /// - The file is not a valid config, see [`Config::parse`]
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, OmegleLibError> {
+        match std::fs::read_to_string(path) {
+            Ok(text) => Self::parse(&text),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    /// Writes the config to a file, creating the directories leading to it
+    ///
+    /// # Errors
+    /// This function fails if the file or its directories cannot be written
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), OmegleLibError> {
+        let path = path.as_ref();
+        if let Some(parent) = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+        {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, self.to_toml()?)?;
+        Ok(())
+    }
+
+    /// Builds an [`Omegle`] from the config, generating a [`RandID`] if there is none
+    ///
+    /// Save the config afterwards to keep the generated [`RandID`]
+    pub fn build(&mut self, status: OmegleStatus) -> Omegle {
+        self.build_with_transport(status, Arc::new(ReqwestTransport::default()))
+    }
+
+    /// Same as [`Config::build`] but the [`Omegle`] sends all of its requests through `transport`
+    pub fn build_with_transport(
+        &mut self,
+        status: OmegleStatus,
+        transport: Arc<dyn Transport>,
+    ) -> Omegle {
+        let topics = self.interests.iter().cloned().collect::<HashSet<_>>();
+        let mut omegle = Omegle::with_transport(status, topics, self.lang, transport);
+        omegle.update_rand_id(*self.rand_id.get_or_insert_with(RandID::new));
+        omegle.update_endpoints(self.endpoints.clone());
+        omegle
+    }
+
+    /// Loads the config at `path`, fetches the status from its endpoints and builds
+    /// an [`Omegle`], saving the config if a [`RandID`] had to be generated
+    ///
+    /// # Examples
+    /// Keep the same profile across runs
+    /// ```rust
+    /// use omegle_rs::config::Config;
+    /// use omegle_rs::omegle::Omegle;
+    ///
+    /// async fn run() -> Omegle {
+    ///     Config::load_omegle("omegle.toml").await.unwrap()
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    /// This function fails if:
+    /// - The config cannot be loaded, see [`Config::load`]
+    /// - The status cannot be fetched
+    /// - The generated [`RandID`] cannot be saved
+    pub async fn load_omegle<P: AsRef<Path>>(path: P) -> Result<Omegle, OmegleLibError> {
+        Self::load_omegle_with(path, Arc::new(ReqwestTransport::default())).await
+    }
+
+    /// Same as [`Config::load_omegle`] but sends all requests through `transport`
+    ///
+    /// # Errors
+    /// This function fails if:
+    /// - The config cannot be loaded, see [`Config::load`]
+    /// - The status cannot be fetched
+    /// - The generated [`RandID`] cannot be saved
+    pub async fn load_omegle_with<P: AsRef<Path>>(
+        path: P,
+        transport: Arc<dyn Transport>,
+    ) -> Result<Omegle, OmegleLibError> {
+        let mut config = Self::load(&path)?;
+        let status =
+            OmegleStatus::get_omegle_status_from(transport.as_ref(), &config.endpoints).await?;
+
+        let had_rand_id = config.rand_id.is_some();
+        let omegle = config.build_with_transport(status, transport);
+        if !had_rand_id {
+            config.save(&path)?;
+        }
+        Ok(omegle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use futures::executor::block_on;
+
+    use crate::transport::MemoryTransport;
+
+    use super::*;
+
+    /// A path in a fresh temporary directory, removed when the test is done with it
+    struct TempPath(PathBuf);
+
+    impl TempPath {
+        fn new(name: &str) -> Self {
+            let dir = std::env::temp_dir()
+                .join(format!("omegle-rs-{name}-{}", String::from(RandID::new())));
+            Self(dir.join("profile").join("omegle.toml"))
+        }
+    }
+
+    impl Drop for TempPath {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(self.0.ancestors().nth(2).expect("path is nested"));
+        }
+    }
+
+    const STATUS: &str =
+        r#"{"count": 1, "servers": ["front1"], "antinudeservers": ["waw1.omegle.com"]}"#;
+
+    #[test]
+    fn parses_partial_config() {
+        let config = Config::parse(
+            r#"
+            lang = "fr"
+            interests = ["knitting"]
+
+            [endpoints]
+            domain = "localhost"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.lang, LangCode::French);
+        assert_eq!(config.interests, vec![String::from("knitting")]);
+        assert_eq!(config.rand_id, None);
+        assert_eq!(config.endpoints, Endpoints::new().domain("localhost"))
+    }
+
+    #[test]
+    fn rejects_invalid_values() {
+        assert!(Config::parse(r#"lang = "xx""#).is_err());
+        assert!(Config::parse(r#"rand_id = "ABCDE1IO""#).is_err())
+    }
+
+    #[test]
+    fn round_trips_through_toml() {
+        let config = Config {
+            lang: LangCode::Spanish,
+            interests: vec![String::from("music")],
+            rand_id: Some(RandID::try_from("ABCDEFGH").unwrap()),
+            endpoints: Endpoints::new().status_url("http://localhost/status"),
+        };
+
+        assert_eq!(Config::parse(&config.to_toml().unwrap()).unwrap(), config)
+    }
+
+    #[test]
+    fn missing_file_gives_default_config() {
+        let path = TempPath::new("missing");
+        assert_eq!(Config::load(&path.0).unwrap(), Config::default())
+    }
+
+    #[test]
+    fn load_omegle_saves_generated_rand_id() {
+        let path = TempPath::new("rand-id");
+        Config {
+            interests: vec![String::from(" Knitting ")],
+            endpoints: Endpoints::new().status_url("http://localhost/status"),
+            ..Config::default()
+        }
+        .save(&path.0)
+        .unwrap();
+        let transport = Arc::new(MemoryTransport::new());
+        transport.push_response("/status", STATUS);
+        transport.push_response("/status", STATUS);
+
+        let first = block_on(Config::load_omegle_with(&path.0, transport.clone())).unwrap();
+        let second = block_on(Config::load_omegle_with(&path.0, transport.clone())).unwrap();
+
+        assert_eq!(first.get_rand_id(), second.get_rand_id());
+        assert_eq!(
+            Config::load(&path.0).unwrap().rand_id,
+            Some(first.get_rand_id())
+        );
+        assert_eq!(first.get_current_interests(), vec!["knitting"]);
+        assert_eq!(transport.requests()[0].url, "http://localhost/status")
+    }
+}
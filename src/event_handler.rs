@@ -0,0 +1,194 @@
+//! Reacting to chat events with callbacks
+//!
+//! Implement [`EventHandler`] for the events you care about and pass it to
+//! [`ChatSession::run`], which polls the chat and calls the matching method for
+//! every event until the chat ends.
+
+use vec1::Vec1;
+
+/// Re-export of the attribute needed to implement [`EventHandler`]
+pub use async_trait::async_trait;
+
+use crate::chat_session::ChatSession;
+use crate::types::{chat_event::ChatEvent, error::OmegleLibError};
+
+/// Trait for reacting to the events of a [`ChatSession`]
+///
+/// Every method does nothing by default, so only the events you care about
+/// need to be implemented. Pass the handler to [`ChatSession::run`] to have
+/// events dispatched to it until the chat ends.
+///
+/// # Examples
+/// Echo every message back to the stranger
+/// ```rust
+/// use omegle_rs::chat_session::ChatSession;
+/// use omegle_rs::event_handler::{async_trait, EventHandler};
+///
+/// struct Echo;
+///
+/// #[async_trait]
+/// impl EventHandler for Echo {
+///     async fn on_message(&mut self, session: &ChatSession, message: String) {
+///         let _ = session.send_message(message).await;
+///     }
+/// }
+///
+/// async fn run(session: ChatSession) {
+///     session.run(&mut Echo).await.unwrap();
+/// }
+/// ```
+#[async_trait]
+pub trait EventHandler: Send {
+    /// Called while waiting for a stranger
+    async fn on_waiting(&mut self, _session: &ChatSession) {}
+
+    /// Called when a stranger is found
+    async fn on_connected(&mut self, _session: &ChatSession) {}
+
+    /// Called with the interests shared with the stranger
+    async fn on_common_likes(&mut self, _session: &ChatSession, _likes: Vec1<String>) {}
+
+    /// Called when the stranger sends a message
+    async fn on_message(&mut self, _session: &ChatSession, _message: String) {}
+
+    /// Called when the stranger starts typing
+    async fn on_typing(&mut self, _session: &ChatSession) {}
+
+    /// Called when the stranger stops typing without sending a message
+    async fn on_stopped_typing(&mut self, _session: &ChatSession) {}
+
+    /// Called when omegle sends a notice about the chat
+    async fn on_server_message(&mut self, _session: &ChatSession, _message: String) {}
+
+    /// Called when the stranger disconnects, the chat is over afterwards
+    async fn on_disconnected(&mut self, _session: &ChatSession) {}
+
+    /// Called when omegle reports an error or events could not be fetched,
+    /// the chat is over afterwards
+    async fn on_error(&mut self, _session: &ChatSession, _error: &OmegleLibError) {}
+
+    /// Called for every event that doesn't have a dedicated method
+    async fn on_event(&mut self, _session: &ChatSession, _event: ChatEvent) {}
+
+    /// Whether [`ChatSession::run`] should stop, checked after every event
+    ///
+    /// Useful for handlers that end the chat themselves, never by default
+    fn is_done(&self) -> bool {
+        false
+    }
+}
+
+/// Hands a single event to the matching method of `handler`
+pub(crate) async fn dispatch<H: EventHandler + ?Sized>(
+    handler: &mut H,
+    session: &ChatSession,
+    event: ChatEvent,
+) {
+    match event {
+        ChatEvent::Waiting => handler.on_waiting(session).await,
+        ChatEvent::Connected => handler.on_connected(session).await,
+        ChatEvent::CommonLikes(likes) => handler.on_common_likes(session, likes).await,
+        ChatEvent::Message(message) => handler.on_message(session, message).await,
+        ChatEvent::StartedTyping => handler.on_typing(session).await,
+        ChatEvent::StoppedTyping => handler.on_stopped_typing(session).await,
+        ChatEvent::ServerMessage(message) => handler.on_server_message(session, message).await,
+        ChatEvent::Disconnected => handler.on_disconnected(session).await,
+        ChatEvent::Error(message) => {
+            handler
+                .on_error(session, &OmegleLibError::OmegleError(message))
+                .await
+        }
+        event => handler.on_event(session, event).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use futures::executor::block_on;
+
+    use crate::chat_session::tests::test_session;
+    use crate::transport::MemoryTransport;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct Recorder {
+        calls: Vec<String>,
+    }
+
+    #[async_trait]
+    impl EventHandler for Recorder {
+        async fn on_connected(&mut self, _session: &ChatSession) {
+            self.calls.push(String::from("connected"))
+        }
+
+        async fn on_message(&mut self, session: &ChatSession, message: String) {
+            session
+                .send_message(format!("echo {message}"))
+                .await
+                .unwrap();
+            self.calls.push(format!("message {message}"))
+        }
+
+        async fn on_disconnected(&mut self, _session: &ChatSession) {
+            self.calls.push(String::from("disconnected"))
+        }
+
+        async fn on_error(&mut self, _session: &ChatSession, error: &OmegleLibError) {
+            self.calls.push(format!("error {error}"))
+        }
+
+        async fn on_event(&mut self, _session: &ChatSession, event: ChatEvent) {
+            self.calls.push(format!("other {event:?}"))
+        }
+    }
+
+    #[test]
+    fn run_dispatches_until_disconnected() {
+        let transport = Arc::new(MemoryTransport::new());
+        transport.push_response("/events", r#"[["waiting"], ["connected"]]"#);
+        transport.push_response("/events", r#"[["gotMessage", "hi"], ["count", 5]]"#);
+        transport.push_response("/events", r#"[["strangerDisconnected"]]"#);
+        transport.push_response("/send", "win");
+        let session = test_session(transport.clone());
+        let mut recorder = Recorder::default();
+
+        block_on(session.run(&mut recorder)).unwrap();
+
+        assert_eq!(
+            recorder.calls,
+            vec!["connected", "message hi", "other Count(5)", "disconnected"]
+        );
+        assert_eq!(transport.requests().len(), 4)
+    }
+
+    #[test]
+    fn run_reports_polling_errors() {
+        let transport = Arc::new(MemoryTransport::new());
+        transport.push_response("/events", "null");
+        let session = test_session(transport);
+        let mut recorder = Recorder::default();
+
+        let resp = block_on(session.run(&mut recorder));
+
+        assert!(matches!(resp, Err(OmegleLibError::JsonError(_))));
+        assert!(recorder.calls[0].starts_with("error "))
+    }
+
+    #[test]
+    fn run_stops_on_server_error() {
+        let transport = Arc::new(MemoryTransport::new());
+        transport.push_response("/events", r#"[["error", "bad"]]"#);
+        let session = test_session(transport);
+        let mut recorder = Recorder::default();
+
+        block_on(session.run(&mut recorder)).unwrap();
+
+        assert_eq!(
+            recorder.calls,
+            vec!["error omegle server responded with 'bad'"]
+        )
+    }
+}
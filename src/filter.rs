@@ -0,0 +1,302 @@
+//! Filtering the content of messages
+//!
+//! A [`FilterPipeline`] is a list of [`FilterRule`]s checked against every message
+//! sent with [`ChatSession::send_message`](crate::chat_session::ChatSession::send_message)
+//! and every [`ChatEvent::Message`](crate::types::chat_event::ChatEvent::Message)
+//! received, once it is set with
+//! [`ChatSession::update_filter`](crate::chat_session::ChatSession::update_filter).
+
+use regex::Regex;
+
+use crate::types::error::OmegleLibError;
+pub use crate::types::filter_match::{Direction, FilterAction, FilterMatch};
+
+/// Matches urls and bare domains of common top level domains
+pub(crate) const LINK_PATTERN: &str =
+    r"(?i)\b(?:https?://|www\.)\S+|\b[a-z0-9-]+\.(?:com|net|org|io|gg|me|co|ly|tv|xyz)\b(?:/\S*)?";
+
+/// A named pattern and what to do with messages it matches
+#[derive(Debug, Clone)]
+pub struct FilterRule {
+    name: String,
+    pattern: Regex,
+    action: FilterAction,
+    direction: Option<Direction>,
+}
+
+impl FilterRule {
+    /// Creates a rule matching a regular expression
+    ///
+    /// # Errors
+    /// This function fails if `pattern` is not a valid regular expression
+    pub fn regex<N: ToString>(
+        name: N,
+        pattern: &str,
+        action: FilterAction,
+    ) -> Result<Self, OmegleLibError> {
+        Ok(Self {
+            name: name.to_string(),
+            pattern: Regex::new(pattern)?,
+            action,
+            direction: None,
+        })
+    }
+
+    /// Creates a rule matching any of `keywords` as a whole word, ignoring case
+    ///
+    /// # Errors
+    /// This function fails if the keywords make the pattern too big
+    pub fn keywords<N, K>(
+        name: N,
+        keywords: impl IntoIterator<Item = K>,
+        action: FilterAction,
+    ) -> Result<Self, OmegleLibError>
+    where
+        N: ToString,
+        K: AsRef<str>,
+    {
+        let alternatives = keywords
+            .into_iter()
+            .map(|keyword| regex::escape(keyword.as_ref().trim()))
+            .filter(|keyword| !keyword.is_empty())
+            .collect::<Vec<_>>();
+        let pattern = if alternatives.is_empty() {
+            // Never matches anything
+            String::from(r"[^\s\S]")
+        } else {
+            format!(r"(?i)\b(?:{})\b", alternatives.join("|"))
+        };
+        Self::regex(name, &pattern, action)
+    }
+
+    /// Creates a rule named `links` matching urls and bare domains
+    pub fn links(action: FilterAction) -> Self {
+        Self::regex("links", LINK_PATTERN, action).expect("preset pattern is valid")
+    }
+
+    /// Creates a rule named `phone numbers` matching runs of at least 8 digits,
+    /// optionally separated by spaces, dots, dashes or parentheses
+    pub fn phone_numbers(action: FilterAction) -> Self {
+        Self::regex("phone numbers", r"\+?\d(?:[\s().-]*\d){7,}", action)
+            .expect("preset pattern is valid")
+    }
+
+    /// Only applies the rule to messages going in one direction
+    pub fn only(mut self, direction: Direction) -> Self {
+        self.direction = Some(direction);
+        self
+    }
+
+    /// Gets the name of the rule
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    /// Gets what the rule does to the messages it matches
+    pub fn get_action(&self) -> FilterAction {
+        self.action
+    }
+
+    fn applies_to(&self, direction: Direction) -> bool {
+        self.direction.is_none_or(|only| only == direction)
+    }
+}
+
+/// Enum describing what should happen to a filtered message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    /// The message can go through, possibly redacted
+    Pass(String),
+    /// The message should be dropped
+    Drop,
+    /// The message should be dropped and the chat disconnected
+    Disconnect,
+}
+
+/// The result of running a message through a [`FilterPipeline`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FilterOutcome {
+    /// What should happen to the message. Decided by the first matching rule that drops
+    /// it or disconnects, otherwise [`Verdict::Pass`] with the redacted message
+    pub verdict: Verdict,
+    /// Every rule that matched, in order. When the message is dropped the last
+    /// one is the rule that dropped it
+    pub matches: Vec<FilterMatch>,
+}
+
+/// An ordered list of [`FilterRule`]s
+///
+/// Rules are checked in the order they were added. Redacting rules replace
+/// what they matched with the replacement (`***` by default) and let the
+/// following rules check the redacted message, the first dropping or
+/// disconnecting rule that matches stops the pipeline.
+///
+/// # Examples
+/// ```rust
+/// use omegle_rs::chat_session::ChatSession;
+/// use omegle_rs::filter::{Direction, FilterAction, FilterPipeline, FilterRule};
+///
+/// async fn run(mut session: ChatSession) {
+///     let filter = FilterPipeline::new()
+///         .rule(FilterRule::keywords("slurs", ["badword"], FilterAction::Disconnect).unwrap())
+///         .rule(FilterRule::links(FilterAction::Drop).only(Direction::Outgoing))
+///         .rule(FilterRule::phone_numbers(FilterAction::Redact));
+///     session.update_filter(Some(filter));
+///     session.send_message("call me at 555 123 4567").await.unwrap();
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct FilterPipeline {
+    rules: Vec<FilterRule>,
+    replacement: String,
+}
+
+impl Default for FilterPipeline {
+    fn default() -> Self {
+        Self {
+            rules: Vec::new(),
+            replacement: String::from("***"),
+        }
+    }
+}
+
+impl FilterPipeline {
+    /// Creates a new [`FilterPipeline`] without any rules
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a rule after the existing ones
+    pub fn rule(mut self, rule: FilterRule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// Sets what redacting rules replace their matches with
+    pub fn replacement<R: ToString>(mut self, replacement: R) -> Self {
+        self.replacement = replacement.to_string();
+        self
+    }
+
+    /// Gets the rules of the pipeline, in order
+    pub fn get_rules(&self) -> &[FilterRule] {
+        &self.rules
+    }
+
+    /// Runs a message going in `direction` through every rule
+    pub fn apply(&self, direction: Direction, message: &str) -> FilterOutcome {
+        let mut message = message.to_string();
+        let mut matches = Vec::new();
+
+        for rule in self.rules.iter().filter(|rule| rule.applies_to(direction)) {
+            if !rule.pattern.is_match(&message) {
+                continue;
+            }
+            matches.push(FilterMatch {
+                rule: rule.name.clone(),
+                action: rule.action,
+                direction,
+            });
+
+            let verdict = match rule.action {
+                FilterAction::Redact => {
+                    message = rule
+                        .pattern
+                        .replace_all(&message, self.replacement.as_str())
+                        .into_owned();
+                    continue;
+                }
+                FilterAction::Drop => Verdict::Drop,
+                FilterAction::Disconnect => Verdict::Disconnect,
+            };
+            return FilterOutcome { verdict, matches };
+        }
+
+        FilterOutcome {
+            verdict: Verdict::Pass(message),
+            matches,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_pipeline() -> FilterPipeline {
+        FilterPipeline::new()
+            .rule(FilterRule::phone_numbers(FilterAction::Redact))
+            .rule(
+                FilterRule::keywords("slurs", ["Badword", " "], FilterAction::Disconnect).unwrap(),
+            )
+            .rule(FilterRule::links(FilterAction::Drop).only(Direction::Outgoing))
+    }
+
+    #[test]
+    fn passes_clean_messages_untouched() {
+        let outcome = test_pipeline().apply(Direction::Outgoing, "hi, asl?");
+        assert_eq!(outcome.verdict, Verdict::Pass(String::from("hi, asl?")));
+        assert!(outcome.matches.is_empty())
+    }
+
+    #[test]
+    fn redacts_and_keeps_checking() {
+        let outcome = test_pipeline().apply(Direction::Incoming, "call +1 (555) 123-4567 badword");
+        assert_eq!(outcome.verdict, Verdict::Disconnect);
+        assert_eq!(
+            outcome.matches,
+            vec![
+                FilterMatch {
+                    rule: String::from("phone numbers"),
+                    action: FilterAction::Redact,
+                    direction: Direction::Incoming,
+                },
+                FilterMatch {
+                    rule: String::from("slurs"),
+                    action: FilterAction::Disconnect,
+                    direction: Direction::Incoming,
+                },
+            ]
+        )
+    }
+
+    #[test]
+    fn keywords_only_match_whole_words() {
+        let outcome = test_pipeline().apply(Direction::Incoming, "badwords are fine");
+        assert_eq!(
+            outcome.verdict,
+            Verdict::Pass(String::from("badwords are fine"))
+        )
+    }
+
+    #[test]
+    fn respects_rule_direction() {
+        let pipeline = test_pipeline().replacement("[redacted]");
+        assert_eq!(
+            pipeline
+                .apply(Direction::Incoming, "see example.com")
+                .verdict,
+            Verdict::Pass(String::from("see example.com"))
+        );
+        assert_eq!(
+            pipeline
+                .apply(Direction::Outgoing, "see https://example.com/x")
+                .verdict,
+            Verdict::Drop
+        );
+        assert_eq!(
+            pipeline
+                .apply(Direction::Outgoing, "text 5551234567")
+                .verdict,
+            Verdict::Pass(String::from("text [redacted]"))
+        )
+    }
+
+    #[test]
+    fn rejects_invalid_patterns() {
+        assert!(matches!(
+            FilterRule::regex("broken", "(", FilterAction::Drop),
+            Err(OmegleLibError::InvalidFilterPattern(_))
+        ))
+    }
+}
@@ -1,4 +1,20 @@
+pub mod ban;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+#[cfg(feature = "bot")]
+pub mod bot;
 pub mod chat_session;
+#[cfg(feature = "config")]
+pub mod config;
+pub mod event_handler;
+pub mod filter;
+pub mod matchmaking;
 pub mod omegle;
+pub mod relay;
+#[cfg(feature = "server")]
+pub mod server;
+pub mod session_manager;
+pub mod spam;
 pub mod status;
+pub mod transport;
 pub mod types;
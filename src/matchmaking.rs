@@ -0,0 +1,334 @@
+//! Pairing strangers looking for a chat
+//!
+//! A [`Matchmaker`] queues clients and pairs those that speak the same [`LangCode`]
+//! and share an interest. Clients without interests are paired with anyone, and
+//! clients with interests fall back to that once they waited long enough without
+//! finding a match. The current time is always passed in, so the same calls always
+//! give the same pairs.
+
+use std::collections::HashSet;
+use std::time::Duration;
+
+use itertools::Itertools;
+use tokio::time::Instant;
+use vec1::Vec1;
+
+use crate::types::chat_event::ChatEvent;
+use crate::types::lang::LangCode;
+
+/// A client waiting in a [`Matchmaker`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ticket<K> {
+    /// What the client is known as
+    pub id: K,
+    /// Clients are only paired with clients of the same language
+    pub lang: LangCode,
+    /// The normalized interests of the client, empty to be paired with anyone
+    pub topics: HashSet<String>,
+    /// When the client started waiting
+    pub queued_at: Instant,
+}
+
+/// Two clients that were paired
+#[derive(Debug, Clone, PartialEq)]
+pub struct Match<K> {
+    /// The client that waited the longest
+    pub first: K,
+    /// The client paired with `first`, which started waiting after it
+    pub second: K,
+    /// The interests both clients share, sorted
+    pub common_likes: Vec<String>,
+}
+
+impl<K> Match<K> {
+    /// The events omegle sends both clients when they are paired
+    pub fn events(&self) -> Vec<ChatEvent> {
+        let mut events = vec![ChatEvent::Connected];
+        if let Ok(common_likes) = Vec1::try_from_vec(self.common_likes.clone()) {
+            events.push(ChatEvent::CommonLikes(common_likes));
+        }
+        events
+    }
+}
+
+/// Queue pairing clients by language and interests
+///
+/// # Examples
+/// ```rust
+/// use omegle_rs::matchmaking::Matchmaker;
+/// use omegle_rs::types::lang::LangCode;
+/// use std::time::Duration;
+/// use tokio::time::Instant;
+///
+/// let mut matchmaker = Matchmaker::new().fallback_after(Some(Duration::from_secs(5)));
+/// let start = Instant::now();
+///
+/// let topics = [String::from("knitting")];
+/// assert!(matchmaker.enqueue(1, LangCode::English, topics, start).is_none());
+/// assert!(matchmaker.enqueue(2, LangCode::English, [], start).is_none());
+///
+/// // Nobody else likes knitting, so after 5 seconds anyone will do
+/// let matches = matchmaker.poll(start + Duration::from_secs(5));
+/// assert_eq!((matches[0].first, matches[0].second), (1, 2));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Matchmaker<K> {
+    waiting: Vec<Ticket<K>>,
+    fallback_after: Option<Duration>,
+}
+
+impl<K> Default for Matchmaker<K> {
+    fn default() -> Self {
+        Self {
+            waiting: Vec::new(),
+            fallback_after: Some(Duration::from_secs(10)),
+        }
+    }
+}
+
+impl<K: Clone + PartialEq> Matchmaker<K> {
+    /// Creates a new empty [`Matchmaker`] falling back to random strangers after 10 seconds
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets how long clients with interests wait for a shared interest before being
+    /// paired with anyone, `None` makes them wait forever
+    pub fn fallback_after(mut self, fallback_after: Option<Duration>) -> Self {
+        self.fallback_after = fallback_after;
+        self
+    }
+
+    /// Gets how long clients with interests wait for a shared interest
+    pub fn get_fallback_after(&self) -> Option<Duration> {
+        self.fallback_after
+    }
+
+    /// Pairs a client with the longest waiting client it matches, or queues it if there is none
+    ///
+    /// Topics are expected to already be normalized
+    pub fn enqueue(
+        &mut self,
+        id: K,
+        lang: LangCode,
+        topics: impl IntoIterator<Item = String>,
+        now: Instant,
+    ) -> Option<Match<K>> {
+        let ticket = Ticket {
+            id,
+            lang,
+            topics: topics.into_iter().collect(),
+            queued_at: now,
+        };
+        let found = self
+            .waiting
+            .iter()
+            .enumerate()
+            .find_map(|(index, waiting)| Some((index, self.pair(waiting, &ticket, now)?)));
+        match found {
+            Some((index, common_likes)) => Some(Match {
+                first: self.waiting.remove(index).id,
+                second: ticket.id,
+                common_likes,
+            }),
+            None => {
+                self.waiting.push(ticket);
+                None
+            }
+        }
+    }
+
+    /// Pairs the clients that match now that their interest search may have timed out
+    pub fn poll(&mut self, now: Instant) -> Vec<Match<K>> {
+        let mut matches = Vec::new();
+        let mut index = 0;
+        while index < self.waiting.len() {
+            let found = self.waiting[index + 1..]
+                .iter()
+                .enumerate()
+                .find_map(|(offset, other)| {
+                    Some((
+                        index + 1 + offset,
+                        self.pair(&self.waiting[index], other, now)?,
+                    ))
+                });
+            match found {
+                Some((other, common_likes)) => {
+                    let second = self.waiting.remove(other).id;
+                    let first = self.waiting.remove(index).id;
+                    matches.push(Match {
+                        first,
+                        second,
+                        common_likes,
+                    });
+                }
+                None => index += 1,
+            }
+        }
+        matches
+    }
+
+    /// Gets when the next interest search after `now` times out, which is when
+    /// [`Matchmaker::poll`] could pair clients that can't be paired yet
+    pub fn next_deadline(&self, now: Instant) -> Option<Instant> {
+        let fallback_after = self.fallback_after?;
+        self.waiting
+            .iter()
+            .filter(|ticket| !ticket.topics.is_empty())
+            .map(|ticket| ticket.queued_at + fallback_after)
+            .filter(|deadline| *deadline > now)
+            .min()
+    }
+
+    /// Takes a client out of the queue, returns whether it was waiting
+    pub fn remove(&mut self, id: &K) -> bool {
+        let before = self.waiting.len();
+        self.waiting.retain(|ticket| &ticket.id != id);
+        self.waiting.len() != before
+    }
+
+    /// Gets whether a client is waiting
+    pub fn is_waiting(&self, id: &K) -> bool {
+        self.waiting.iter().any(|ticket| &ticket.id == id)
+    }
+
+    /// Gets the waiting clients, longest waiting first
+    pub fn get_waiting(&self) -> &[Ticket<K>] {
+        &self.waiting
+    }
+
+    /// Gets how many clients are waiting
+    pub fn len(&self) -> usize {
+        self.waiting.len()
+    }
+
+    /// Gets whether no client is waiting
+    pub fn is_empty(&self) -> bool {
+        self.waiting.is_empty()
+    }
+
+    /// Gets the common likes of two clients if they can be paired
+    fn pair(&self, first: &Ticket<K>, second: &Ticket<K>, now: Instant) -> Option<Vec<String>> {
+        if first.lang != second.lang {
+            return None;
+        }
+        let common_likes = first
+            .topics
+            .intersection(&second.topics)
+            .cloned()
+            .sorted()
+            .collect::<Vec<_>>();
+        let wants_anyone = |ticket: &Ticket<K>| {
+            ticket.topics.is_empty()
+                || self.fallback_after.is_some_and(|fallback_after| {
+                    now.saturating_duration_since(ticket.queued_at) >= fallback_after
+                })
+        };
+        (!common_likes.is_empty() || (wants_anyone(first) && wants_anyone(second)))
+            .then_some(common_likes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use vec1::vec1;
+
+    use super::*;
+
+    fn topics(topics: &[&str]) -> Vec<String> {
+        topics.iter().map(|topic| topic.to_string()).collect()
+    }
+
+    #[test]
+    fn pairs_shared_interests_oldest_first() {
+        let now = Instant::now();
+        let mut matchmaker = Matchmaker::new();
+        assert_eq!(
+            matchmaker.enqueue("a", LangCode::English, topics(&["music"]), now),
+            None
+        );
+        assert_eq!(
+            matchmaker.enqueue("b", LangCode::English, topics(&["books"]), now),
+            None
+        );
+
+        let found = matchmaker
+            .enqueue("c", LangCode::English, topics(&["music", "books"]), now)
+            .unwrap();
+
+        assert_eq!(
+            found,
+            Match {
+                first: "a",
+                second: "c",
+                common_likes: topics(&["music"]),
+            }
+        );
+        assert_eq!(
+            found.events(),
+            vec![
+                ChatEvent::Connected,
+                ChatEvent::CommonLikes(vec1![String::from("music")])
+            ]
+        );
+        assert!(matchmaker.is_waiting(&"b"))
+    }
+
+    #[test]
+    fn never_pairs_different_languages() {
+        let now = Instant::now();
+        let mut matchmaker = Matchmaker::new().fallback_after(Some(Duration::ZERO));
+        matchmaker.enqueue(1, LangCode::English, [], now);
+
+        assert_eq!(matchmaker.enqueue(2, LangCode::French, [], now), None);
+        assert!(matchmaker.poll(now + Duration::from_secs(60)).is_empty());
+        assert_eq!(matchmaker.next_deadline(now), None);
+        assert_eq!(
+            matchmaker
+                .enqueue(3, LangCode::French, [], now)
+                .unwrap()
+                .events(),
+            vec![ChatEvent::Connected]
+        )
+    }
+
+    #[test]
+    fn falls_back_to_random_after_timeout() {
+        let start = Instant::now();
+        let mut matchmaker = Matchmaker::new().fallback_after(Some(Duration::from_secs(5)));
+        matchmaker.enqueue(1, LangCode::English, topics(&["knitting"]), start);
+        matchmaker.enqueue(2, LangCode::English, [], start + Duration::from_secs(1));
+
+        assert_eq!(
+            matchmaker.next_deadline(start),
+            Some(start + Duration::from_secs(5))
+        );
+        assert!(matchmaker.poll(start + Duration::from_secs(4)).is_empty());
+        assert_eq!(
+            matchmaker.poll(start + Duration::from_secs(5)),
+            vec![Match {
+                first: 1,
+                second: 2,
+                common_likes: Vec::new(),
+            }]
+        );
+        assert!(matchmaker.is_empty());
+        assert_eq!(matchmaker.next_deadline(start), None)
+    }
+
+    #[test]
+    fn waits_forever_without_fallback() {
+        let start = Instant::now();
+        let mut matchmaker = Matchmaker::new().fallback_after(None);
+        matchmaker.enqueue(1, LangCode::Spanish, topics(&["chess"]), start);
+        matchmaker.enqueue(2, LangCode::Spanish, [], start);
+
+        assert_eq!(matchmaker.next_deadline(start), None);
+        assert!(matchmaker
+            .poll(start + Duration::from_secs(3600))
+            .is_empty());
+        assert!(matchmaker.remove(&1));
+        assert!(!matchmaker.remove(&1));
+        assert_eq!(matchmaker.len(), 1)
+    }
+}
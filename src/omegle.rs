@@ -1,21 +1,56 @@
 use itertools::Itertools;
 use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use reqwest::Url;
+use tokio::time::Instant;
 
 use crate::{
+    ban::{BanPolicy, BanRecord, BanStatus},
     chat_session::ChatSession,
     status::OmegleStatus,
-    types::{client_id::ClientID, lang::LangCode, rand_id::RandID},
+    transport::{post_form_json, ReqwestTransport, Transport},
+    types::{
+        chat_mode::{Capability, ChatMode},
+        chat_server::ChatServer,
+        endpoints::Endpoints,
+        error::OmegleLibError,
+        lang::LangCode,
+        rand_id::RandID,
+        start_response::StartResponse,
+    },
 };
 
-use reqwest::Client;
+/// The maximum amount of chars an interest can have
+pub const MAX_INTEREST_LENGTH: usize = 50;
+
+/// Trims and lowercases an interest, checking that it isn't empty or too long
+pub(crate) fn normalize_interest(interest: &str) -> Result<String, OmegleLibError> {
+    let normalized = interest.trim().to_lowercase();
+    if normalized.is_empty() || normalized.chars().count() > MAX_INTEREST_LENGTH {
+        Err(OmegleLibError::InvalidInterest(interest.to_string()))
+    } else {
+        Ok(normalized)
+    }
+}
 
 /// Struct representing an Omegle Client, a factory for creating [`ChatSession`]
 pub struct Omegle {
-    rand_id: RandID,
-    client: Client,
+    rand_id: Mutex<RandID>,
+    transport: Arc<dyn Transport>,
+    #[cfg(feature = "proxy")]
+    proxy_pool: Option<Arc<crate::transport::ProxyPool>>,
+    endpoints: Arc<Endpoints>,
     status: OmegleStatus,
     topics: HashSet<String>,
     lang: LangCode,
+    mode: ChatMode,
+    capabilities: Vec<Capability>,
+    ban_policy: BanPolicy,
+    ban_record: Arc<BanRecord>,
+    #[cfg(feature = "blocking")]
+    blocking_runtime: std::sync::OnceLock<Arc<tokio::runtime::Runtime>>,
 }
 
 impl Omegle {
@@ -26,23 +61,150 @@ impl Omegle {
 
     /// Gets the currently selected langauge
     pub fn get_current_lang(&self) -> LangCode {
-        return self.lang;
+        self.lang
+    }
+
+    /// Updates the [`RandID`] sent when starting a chat
+    ///
+    /// A new one is generated for every [`Omegle`] instance, set it to keep the same
+    /// one across runs like the website does with its cookie
+    pub fn update_rand_id(&mut self, new_rand_id: RandID) {
+        *self.rand_id.get_mut().expect("lock is not poisoned") = new_rand_id
+    }
+
+    /// Gets the [`RandID`] sent when starting a chat
+    pub fn get_rand_id(&self) -> RandID {
+        *self.rand_id.lock().expect("lock is not poisoned")
+    }
+
+    /// Updates how bans reported by the chats of this instance are handled
+    pub fn update_ban_policy(&mut self, new_ban_policy: BanPolicy) {
+        self.ban_policy = new_ban_policy
+    }
+
+    /// Gets how bans reported by the chats of this instance are handled
+    pub fn get_ban_policy(&self) -> &BanPolicy {
+        &self.ban_policy
+    }
+
+    /// Gets when a chat of this instance was last banned, until the next chat is
+    /// started after the cooldown
+    pub fn get_banned_at(&self) -> Option<Instant> {
+        self.ban_record.get_banned_at()
+    }
+
+    /// Gets how long is left until new chats can be started again, if cooling down from a ban
+    pub fn get_ban_remaining(&self) -> Option<Duration> {
+        self.ban_record
+            .get_remaining(self.ban_policy.get_cooldown(), Instant::now())
+    }
+
+    /// Forgets the last ban, so new chats can be started right away
+    pub fn clear_ban(&self) {
+        self.ban_record.clear()
+    }
+
+    /// Updates where the chat and verification servers are reached
+    ///
+    /// Only affects chats started afterwards
+    pub fn update_endpoints(&mut self, new_endpoints: Endpoints) {
+        self.endpoints = Arc::new(new_endpoints)
+    }
+
+    /// Gets where the chat and verification servers are reached
+    pub fn get_endpoints(&self) -> &Endpoints {
+        &self.endpoints
+    }
+
+    /// Updates the pool of proxies new chats are started through, requires the `proxy` feature
+    ///
+    /// Every chat is pinned to one proxy of the pool, `None` (the default) sends
+    /// requests through the transport of this instance.
+    #[cfg(feature = "proxy")]
+    pub fn update_proxy_pool(&mut self, new_proxy_pool: Option<Arc<crate::transport::ProxyPool>>) {
+        self.proxy_pool = new_proxy_pool
+    }
+
+    /// Gets the pool of proxies new chats are started through, if there is one
+    #[cfg(feature = "proxy")]
+    pub fn get_proxy_pool(&self) -> Option<&Arc<crate::transport::ProxyPool>> {
+        self.proxy_pool.as_ref()
+    }
+
+    /// Updates the section of omegle new chats are started in
+    ///
+    /// # Examples
+    /// Start video chats instead of text chats
+    /// ```rust
+    /// use omegle_rs::omegle::Omegle;
+    /// use omegle_rs::types::chat_mode::ChatMode;
+    ///
+    /// fn use_video(omegle: &mut Omegle) {
+    ///     omegle.update_mode(ChatMode::Video)
+    /// }
+    /// ```
+    pub fn update_mode(&mut self, new_mode: ChatMode) {
+        self.mode = new_mode
+    }
+
+    /// Gets the currently selected [`ChatMode`]
+    pub fn get_current_mode(&self) -> &ChatMode {
+        &self.mode
+    }
+
+    /// Updates the capabilities advertised to omegle when starting a chat
+    ///
+    /// Defaults to [`Capability::DEFAULT`]
+    pub fn update_capabilities(&mut self, new_capabilities: Vec<Capability>) {
+        self.capabilities = new_capabilities
+    }
+
+    /// Gets the currently advertised capabilities
+    pub fn get_current_capabilities(&self) -> &[Capability] {
+        &self.capabilities
     }
 
     /// Add a new interest
     ///
+    /// The interest is trimmed and lowercased before being added, so `" Knitting "`
+    /// and `"knitting"` are the same interest.
+    ///
     /// If the interest was already added returns false, otherwise returns true
-    pub fn add_interest<M: ToString>(&mut self, new_interest: M) -> bool {
-        let new_interest_string = new_interest.to_string();
-        self.topics.insert(new_interest_string)
+    ///
+    /// # Errors
+    /// Returns [Err] if the interest is empty or longer than [`MAX_INTEREST_LENGTH`] chars
+    pub fn add_interest<M: ToString>(&mut self, new_interest: M) -> Result<bool, OmegleLibError> {
+        let new_interest_string = normalize_interest(&new_interest.to_string())?;
+        Ok(self.topics.insert(new_interest_string))
     }
 
     /// Tries to remove an interest
     ///
+    /// The interest is normalized the same way as in [`Omegle::add_interest`]
+    ///
     /// If the interest was present returns true, otherwise returns false
     pub fn remove_interest<M: ToString>(&mut self, interest_to_remove: M) -> bool {
-        let interest_to_remove_string = interest_to_remove.to_string();
-        self.topics.remove(&interest_to_remove_string)
+        normalize_interest(&interest_to_remove.to_string())
+            .is_ok_and(|interest_to_remove_string| self.topics.remove(&interest_to_remove_string))
+    }
+
+    /// Replaces every interest at once
+    ///
+    /// The interests are normalized the same way as in [`Omegle::add_interest`]
+    ///
+    /// # Errors
+    /// Returns [Err] if one of the interests is invalid, in which case the current
+    /// interests are kept
+    pub fn update_interests<I, M>(&mut self, new_interests: I) -> Result<(), OmegleLibError>
+    where
+        I: IntoIterator<Item = M>,
+        M: ToString,
+    {
+        self.topics = new_interests
+            .into_iter()
+            .map(|interest| normalize_interest(&interest.to_string()))
+            .collect::<Result<_, _>>()?;
+        Ok(())
     }
 
     /// Gets a vec of refrences to the current interests
@@ -57,6 +219,10 @@ impl Omegle {
 
     /// Creates a new instance of [`Omegle`] given some topics and the desired [`LangCode`]
     ///
+    /// Topics are normalized the same way as in [`Omegle::add_interest`]. Unlike
+    /// [`Omegle::add_interest`], topics that are invalid don't make this fail but are
+    /// silently skipped, add them one by one to find out which ones were rejected
+    ///
     /// # Examples
     /// Create a new [`Omegle`] instance with no interests and request to talk in English
     /// ```rust
@@ -86,12 +252,61 @@ impl Omegle {
     /// }
     /// ```
     pub fn new(status: OmegleStatus, topics: HashSet<String>, lang: LangCode) -> Self {
+        Self::with_transport(status, topics, lang, Arc::new(ReqwestTransport::default()))
+    }
+
+    /// Creates a new instance of [`Omegle`] that sends all of its requests through `transport`
+    ///
+    /// Every [`ChatSession`] created by this instance shares the same transport. Topics
+    /// are handled the same way as in [`Omegle::new`], invalid ones are silently skipped
+    ///
+    /// # Examples
+    /// Create a new [`Omegle`] instance that answers with scripted responses
+    /// ```rust
+    /// use omegle_rs::omegle::Omegle;
+    /// use omegle_rs::status::OmegleStatus;
+    /// use omegle_rs::transport::MemoryTransport;
+    /// use omegle_rs::types::lang::LangCode;
+    /// use std::collections::HashSet;
+    /// use std::sync::Arc;
+    ///
+    /// async fn run() -> Omegle {
+    ///     let transport = Arc::new(MemoryTransport::new());
+    ///     transport.push_response(
+    ///         "/status",
+    ///         r#"{"count": 1, "servers": ["front1"], "antinudeservers": ["waw1.omegle.com"]}"#,
+    ///     );
+    ///     let server_status = OmegleStatus::get_omegle_status_with(transport.as_ref())
+    ///         .await
+    ///         .unwrap();
+    ///     Omegle::with_transport(server_status, HashSet::new(), LangCode::English, transport)
+    /// }
+    /// ```
+    pub fn with_transport(
+        status: OmegleStatus,
+        topics: HashSet<String>,
+        lang: LangCode,
+        transport: Arc<dyn Transport>,
+    ) -> Self {
+        let topics = topics
+            .iter()
+            .filter_map(|topic| normalize_interest(topic).ok())
+            .collect();
         Self {
-            rand_id: RandID::new(),
-            client: Client::new(),
+            rand_id: Mutex::new(RandID::new()),
+            transport,
+            #[cfg(feature = "proxy")]
+            proxy_pool: None,
+            endpoints: Arc::new(Endpoints::default()),
             status,
             topics,
             lang,
+            mode: ChatMode::default(),
+            capabilities: Capability::DEFAULT.to_vec(),
+            ban_policy: BanPolicy::default(),
+            ban_record: Arc::new(BanRecord::default()),
+            #[cfg(feature = "blocking")]
+            blocking_runtime: std::sync::OnceLock::new(),
         }
     }
 
@@ -116,45 +331,389 @@ impl Omegle {
     /// This function fails if:
     /// - The omegle server cannot be reached
     /// - The response was unexpected (Ex: Error on omegle's end or response was malformed)
-    pub async fn new_chat(&self) -> Result<ChatSession, reqwest::Error> {
+    /// - Every proxy of the [proxy pool](Omegle::update_proxy_pool) was removed
+    /// - A chat was banned less than the [ban policy](Omegle::update_ban_policy) cooldown ago,
+    ///   see [`OmegleLibError::Banned`]. The default cooldown is zero, so this never happens
+    ///   unless one is set
+    pub async fn new_chat(&self) -> Result<ChatSession, OmegleLibError> {
+        self.check_ban()?;
         let chat_server = self.status.get_chat_server();
-        let chat_server_string = String::from(chat_server);
-        let rand_id = String::from(self.rand_id);
-        let check_server = self.status.get_check_server();
-        let check_server_string = String::from(check_server);
-
-        let check_code = self
-            .client
-            .post(format!("http://{check_server_string}/check"))
-            .send()
-            .await?
-            .text()
-            .await?;
-        let lang_code = self.lang.to_string();
-
-        let resp = if self.topics.is_empty() {
-            self
-            .client
-            .post(format!("http://{chat_server_string}.omegle.com/start?caps=recaptcha2,t3&spid=&randid={rand_id}&cc={check_code}&lang={lang_code}"))
-            .send()
-            .await?
-            .json::<ClientID>()
-            .await?
-        } else {
-            let topics_as_string = self.topics.iter().join(",");
-            self
-            .client
-            .post(format!("http://{chat_server_string}.omegle.com/start?caps=recaptcha2,t3&spid=&randid={rand_id}&cc={check_code}&topics={topics_as_string}&lang={lang_code}"))
-            .send()
-            .await?
-            .json::<ClientID>()
-            .await?
-        };
-
-        Ok(ChatSession {
-            client_id: resp,
-            client: self.client.clone(),
-            server: chat_server,
-        })
+        let transport = self.session_transport()?;
+
+        let check_code = transport.post_form(&self.check_url(), &[]).await?;
+
+        let resp = post_form_json::<StartResponse>(
+            transport.as_ref(),
+            &self.start_url(chat_server, &check_code),
+            &[],
+        )
+        .await?;
+
+        let mut session = ChatSession::new(
+            resp.client_id,
+            transport,
+            chat_server,
+            self.endpoints.clone(),
+            resp.into_initial_events(),
+        );
+        session.ban_record = self.ban_record.clone();
+        Ok(session)
+    }
+
+    /// Blocking version of [`Omegle::new_chat`], returns a [`blocking::ChatSession`](crate::blocking::ChatSession)
+    ///
+    /// # Examples
+    /// Start a new chat without an async runtime
+    /// ```rust
+    /// use omegle_rs::omegle::Omegle;
+    /// use omegle_rs::status::OmegleStatus;
+    /// use omegle_rs::types::lang::LangCode;
+    /// use omegle_rs::blocking::ChatSession;
+    /// use std::collections::HashSet;
+    ///
+    /// fn run() -> ChatSession {
+    ///     let server_status = OmegleStatus::get_omegle_status_blocking().unwrap();
+    ///     let omegle = Omegle::new(server_status, HashSet::new(), LangCode::English);
+    ///     omegle.new_chat_blocking().unwrap()
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    /// This function fails if:
+    /// - The omegle server cannot be reached
+    /// - The response was unexpected (Ex: Error on omegle's end or response was malformed)
+    /// - The runtime blocking chats run on could not be created
+    /// - A chat was banned less than the [ban policy](Omegle::update_ban_policy) cooldown ago,
+    ///   which never happens with the default zero cooldown
+    ///
+    /// Chats are started the same way as [`Omegle::new_chat`], through the same transport,
+    /// on a small tokio runtime created the first time and kept by this instance
+    #[cfg(feature = "blocking")]
+    pub fn new_chat_blocking(&self) -> Result<crate::blocking::ChatSession, OmegleLibError> {
+        let runtime = self.blocking_runtime()?;
+        let session = runtime.block_on(self.new_chat())?;
+        Ok(crate::blocking::ChatSession { session, runtime })
+    }
+
+    /// Gets the runtime blocking chats run on, creating it the first time
+    #[cfg(feature = "blocking")]
+    fn blocking_runtime(&self) -> Result<Arc<tokio::runtime::Runtime>, OmegleLibError> {
+        if let Some(runtime) = self.blocking_runtime.get() {
+            return Ok(runtime.clone());
+        }
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+        Ok(self
+            .blocking_runtime
+            .get_or_init(|| Arc::new(runtime))
+            .clone())
+    }
+
+    /// Applies the [`BanPolicy`] before starting a chat
+    fn check_ban(&self) -> Result<(), OmegleLibError> {
+        let status = self
+            .ban_record
+            .check(self.ban_policy.get_cooldown(), Instant::now());
+        let new_ban = matches!(
+            status,
+            BanStatus::Expired { handled: false } | BanStatus::CoolingDown { handled: false, .. }
+        );
+        if new_ban && self.ban_policy.is_rotate_rand_id() {
+            *self.rand_id.lock().expect("lock is not poisoned") = RandID::new();
+        }
+
+        match status {
+            BanStatus::Clear => Ok(()),
+            BanStatus::Expired { .. } => {
+                #[cfg(feature = "proxy")]
+                if let Some(proxy_pool) = &self.proxy_pool {
+                    proxy_pool.restore_banned();
+                }
+                Ok(())
+            }
+            BanStatus::CoolingDown { remaining, .. } => {
+                // The banned proxy was taken out of the pool, the others can keep going
+                #[cfg(feature = "proxy")]
+                if self.ban_policy.is_switch_proxy()
+                    && self
+                        .proxy_pool
+                        .as_ref()
+                        .is_some_and(|proxy_pool| !proxy_pool.get_healthy().is_empty())
+                {
+                    return Ok(());
+                }
+                Err(OmegleLibError::Banned(remaining))
+            }
+        }
+    }
+
+    /// Gets the transport a new chat sends its requests through
+    #[cfg(feature = "proxy")]
+    fn session_transport(&self) -> Result<Arc<dyn Transport>, OmegleLibError> {
+        match &self.proxy_pool {
+            Some(proxy_pool) => Ok(Arc::new(proxy_pool.pin()?)),
+            None => Ok(self.transport.clone()),
+        }
+    }
+
+    /// Gets the transport a new chat sends its requests through
+    #[cfg(not(feature = "proxy"))]
+    fn session_transport(&self) -> Result<Arc<dyn Transport>, OmegleLibError> {
+        Ok(self.transport.clone())
+    }
+
+    /// Builds the url used to get a check code from the verification server
+    fn check_url(&self) -> String {
+        self.endpoints.check_url(self.status.get_check_server())
+    }
+
+    /// Builds the url used to start a new chat on the given chat server
+    fn start_url(&self, chat_server: ChatServer, check_code: &str) -> String {
+        let mut params = vec![
+            ("caps", self.capabilities.iter().join(",")),
+            ("firstevents", String::from("1")),
+            ("spid", String::new()),
+            ("randid", String::from(self.get_rand_id())),
+            ("cc", check_code.to_string()),
+        ];
+        if !self.topics.is_empty() {
+            // Omegle expects the topics as a JSON array
+            let topics_json = serde_json::to_string(&self.topics.iter().collect_vec())
+                .expect("a list of strings is always valid JSON");
+            params.push(("topics", topics_json));
+        }
+        params.push(("lang", self.lang.to_string()));
+        params.extend(self.mode.start_params());
+
+        Url::parse_with_params(&self.endpoints.chat_url(chat_server, "start"), params)
+            .expect("chat server urls are always valid")
+            .into()
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use futures::executor::block_on;
+    use vec1::vec1;
+
+    use crate::transport::MemoryTransport;
+    use crate::types::chat_event::ChatEvent;
+    use crate::types::check_server::CheckServer;
+    use crate::types::college::College;
+
+    use super::*;
+
+    pub(crate) fn test_status() -> OmegleStatus {
+        OmegleStatus {
+            count: 1,
+            servers: vec1![ChatServer { id_number: 1 }],
+            antinudeservers: vec1![CheckServer(1)],
+            rtmfp: None,
+            spy_queue_time: None,
+            spyee_queue_time: None,
+        }
+    }
+
+    #[test]
+    fn new_chat_checks_then_starts() {
+        let transport = Arc::new(MemoryTransport::new());
+        transport.push_response("/check", "checkcode");
+        transport.push_response("/start", r#""central1:aaaaaaaaaaaaaaaaaaaaaaaaaaaaaa""#);
+        let omegle = Omegle::with_transport(
+            test_status(),
+            HashSet::new(),
+            LangCode::English,
+            transport.clone(),
+        );
+
+        let session = block_on(omegle.new_chat()).unwrap();
+
+        assert_eq!(
+            String::from(session.client_id),
+            "central1:aaaaaaaaaaaaaaaaaaaaaaaaaaaaaa"
+        );
+        let requests = transport.requests();
+        assert_eq!(requests[0].url, "http://waw1.omegle.com/check");
+        assert!(requests[1].url.contains("caps=recaptcha2%2Ct3"));
+        assert!(requests[1].url.contains("cc=checkcode"));
+        assert!(!requests[1].url.contains("webrtc=1"))
+    }
+
+    #[test]
+    fn new_chat_uses_endpoints_and_rand_id() {
+        let transport = Arc::new(MemoryTransport::new());
+        transport.push_response("/check", "checkcode");
+        transport.push_response("/start", r#""central1:aaaaaaaaaaaaaaaaaaaaaaaaaaaaaa""#);
+        let mut omegle = Omegle::with_transport(
+            test_status(),
+            HashSet::new(),
+            LangCode::English,
+            transport.clone(),
+        );
+        omegle.update_endpoints(Endpoints::new().domain("localhost"));
+        omegle.update_rand_id(RandID::try_from("ABCDEFGH").unwrap());
+
+        let session = block_on(omegle.new_chat()).unwrap();
+
+        let requests = transport.requests();
+        assert_eq!(requests[0].url, "http://waw1.localhost/check");
+        assert!(requests[1]
+            .url
+            .starts_with("http://front1.localhost/start?"));
+        assert!(requests[1].url.contains("randid=ABCDEFGH"));
+        assert_eq!(
+            session.endpoints.chat_url(session.server, "send"),
+            "http://front1.localhost/send"
+        )
+    }
+
+    #[test]
+    fn new_chat_encodes_topics_as_json_array() {
+        let transport = Arc::new(MemoryTransport::new());
+        transport.push_response("/check", "checkcode");
+        transport.push_response("/start", r#""central1:aaaaaaaaaaaaaaaaaaaaaaaaaaaaaa""#);
+        let mut omegle = Omegle::with_transport(
+            test_status(),
+            HashSet::new(),
+            LangCode::English,
+            transport.clone(),
+        );
+        omegle.add_interest("rock & roll #1 \"café\"").unwrap();
+
+        block_on(omegle.new_chat()).unwrap();
+
+        let url = Url::parse(&transport.requests()[1].url).unwrap();
+        let topics = url
+            .query_pairs()
+            .find(|(key, _)| key == "topics")
+            .map(|(_, value)| value.into_owned());
+        assert_eq!(topics.as_deref(), Some(r#"["rock & roll #1 \"café\""]"#));
+        assert!(url
+            .query_pairs()
+            .any(|(key, value)| key == "lang" && value == "en"))
+    }
+
+    #[test]
+    fn add_interest_normalizes() {
+        let mut omegle = Omegle::new(test_status(), HashSet::new(), LangCode::English);
+        assert!(omegle.add_interest("  Knitting ").unwrap());
+        assert!(!omegle.add_interest("knitting").unwrap());
+        assert_eq!(omegle.get_current_interests(), vec!["knitting"]);
+        assert!(omegle.remove_interest("KNITTING"))
+    }
+
+    #[test]
+    fn new_skips_invalid_topics() {
+        let topics = [" Music ", "   ", &"a".repeat(MAX_INTEREST_LENGTH + 1)]
+            .iter()
+            .map(|topic| topic.to_string())
+            .collect();
+        let omegle = Omegle::new(test_status(), topics, LangCode::English);
+        assert_eq!(omegle.get_current_interests(), vec!["music"])
+    }
+
+    #[test]
+    fn add_interest_rejects_invalid() {
+        let mut omegle = Omegle::new(test_status(), HashSet::new(), LangCode::English);
+        assert!(matches!(
+            omegle.add_interest("   "),
+            Err(OmegleLibError::InvalidInterest(_))
+        ));
+        assert!(matches!(
+            omegle.add_interest("a".repeat(MAX_INTEREST_LENGTH + 1)),
+            Err(OmegleLibError::InvalidInterest(_))
+        ));
+        assert!(omegle.get_current_interests().is_empty())
+    }
+
+    #[test]
+    fn update_interests_keeps_current_ones_on_error() {
+        let mut omegle = Omegle::new(test_status(), HashSet::new(), LangCode::English);
+        omegle.add_interest("music").unwrap();
+
+        assert!(matches!(
+            omegle.update_interests(["books", "   "]),
+            Err(OmegleLibError::InvalidInterest(_))
+        ));
+        assert_eq!(omegle.get_current_interests(), vec!["music"]);
+
+        omegle.update_interests([" Books "]).unwrap();
+        assert_eq!(omegle.get_current_interests(), vec!["books"])
+    }
+
+    #[test]
+    fn new_chat_requests_video_when_enabled() {
+        let transport = Arc::new(MemoryTransport::new());
+        transport.push_response("/check", "checkcode");
+        transport.push_response("/start", r#""central1:aaaaaaaaaaaaaaaaaaaaaaaaaaaaaa""#);
+        let mut omegle = Omegle::with_transport(
+            test_status(),
+            HashSet::new(),
+            LangCode::English,
+            transport.clone(),
+        );
+        omegle.update_mode(ChatMode::Video);
+
+        block_on(omegle.new_chat()).unwrap();
+
+        assert!(transport.requests()[1].url.ends_with("&webrtc=1"))
+    }
+
+    #[test]
+    fn new_chat_sends_college_credentials() {
+        let transport = Arc::new(MemoryTransport::new());
+        transport.push_response("/check", "checkcode");
+        transport.push_response("/start", r#""central1:aaaaaaaaaaaaaaaaaaaaaaaaaaaaaa""#);
+        let mut omegle = Omegle::with_transport(
+            test_status(),
+            HashSet::new(),
+            LangCode::English,
+            transport.clone(),
+        );
+        omegle.update_mode(ChatMode::College(College::new("mit.edu", "token", true)));
+
+        block_on(omegle.new_chat()).unwrap();
+
+        assert!(transport.requests()[1]
+            .url
+            .ends_with("&college=mit.edu&college_auth=token&any_college=1"))
+    }
+
+    #[test]
+    fn new_chat_keeps_initial_events() {
+        let transport = Arc::new(MemoryTransport::new());
+        transport.push_response("/check", "checkcode");
+        transport.push_response(
+            "/start",
+            r#"{"clientID": "central1:aaaaaaaaaaaaaaaaaaaaaaaaaaaaaa", "events": [["waiting"], ["connected"]]}"#,
+        );
+        let omegle = Omegle::with_transport(
+            test_status(),
+            HashSet::new(),
+            LangCode::English,
+            transport.clone(),
+        );
+
+        let session = block_on(omegle.new_chat()).unwrap();
+        let events = block_on(session.get_events()).unwrap();
+
+        assert_eq!(
+            events.into_vec(),
+            vec![ChatEvent::Waiting, ChatEvent::Connected]
+        );
+        assert_eq!(transport.requests().len(), 2)
+    }
+
+    #[test]
+    fn new_chat_errors_on_malformed_client_id() {
+        let transport = Arc::new(MemoryTransport::new());
+        transport.push_response("/check", "checkcode");
+        transport.push_response("/start", r#""fail""#);
+        let omegle =
+            Omegle::with_transport(test_status(), HashSet::new(), LangCode::English, transport);
+
+        let resp = block_on(omegle.new_chat());
+        assert!(matches!(resp, Err(OmegleLibError::JsonError(_))))
     }
 }
@@ -0,0 +1,298 @@
+//! Connecting two strangers to each other
+//!
+//! A [`Relay`] forwards messages, typing indicators and disconnects between two
+//! [`ChatSession`]s, so each stranger thinks they are talking to the other one
+//! directly. Messages can be edited or dropped on the way and the operator can
+//! inject their own.
+
+use std::sync::Mutex;
+
+use futures::future::{self, Either};
+
+use crate::chat_session::ChatSession;
+use crate::types::{chat_event::ChatEvent, error::OmegleLibError};
+
+type FilterFn = Box<dyn Fn(Side, &str) -> Option<String> + Send + Sync>;
+type ObserverFn = Box<dyn Fn(Side, &ChatEvent) + Send + Sync>;
+
+/// Enum describing one of the two strangers of a [`Relay`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Side {
+    First,
+    Second,
+}
+
+impl Side {
+    /// Gets the stranger on the other end of the relay
+    pub fn other(self) -> Side {
+        match self {
+            Side::First => Side::Second,
+            Side::Second => Side::First,
+        }
+    }
+
+    fn index(self) -> usize {
+        match self {
+            Side::First => 0,
+            Side::Second => 1,
+        }
+    }
+}
+
+/// Struct relaying a chat between two strangers
+///
+/// Messages sent to a stranger that isn't connected yet are held back until
+/// they connect. Once either stranger leaves, the other one is disconnected too.
+///
+/// # Examples
+/// Relay two strangers, censoring a word and showing the conversation
+/// ```rust
+/// use omegle_rs::omegle::Omegle;
+/// use omegle_rs::relay::Relay;
+///
+/// async fn run(omegle: Omegle) {
+///     let relay = Relay::new(omegle.new_chat().await.unwrap(), omegle.new_chat().await.unwrap())
+///         .filter(|_from, message| Some(message.replace("omegle", "******")))
+///         .observe(|from, event| println!("{from:?}: {event:?}"));
+///     let left_first = relay.run().await.unwrap();
+/// }
+/// ```
+pub struct Relay {
+    sessions: [ChatSession; 2],
+    filter: Option<FilterFn>,
+    observer: Option<ObserverFn>,
+}
+
+/// State shared by both directions of a running relay
+#[derive(Default)]
+struct Stranger {
+    connected: bool,
+    held_back: Vec<String>,
+}
+
+impl Relay {
+    /// Creates a new [`Relay`] between two freshly started sessions
+    pub fn new(first: ChatSession, second: ChatSession) -> Self {
+        Self {
+            sessions: [first, second],
+            filter: None,
+            observer: None,
+        }
+    }
+
+    /// Sets the hook every relayed message goes through
+    ///
+    /// `filter` gets the stranger that sent the message and the message, the
+    /// returned message is forwarded instead, or nothing if it returns `None`
+    pub fn filter<F>(mut self, filter: F) -> Self
+    where
+        F: Fn(Side, &str) -> Option<String> + Send + Sync + 'static,
+    {
+        self.filter = Some(Box::new(filter));
+        self
+    }
+
+    /// Sets the hook called with every event received from either stranger
+    pub fn observe<F>(mut self, observer: F) -> Self
+    where
+        F: Fn(Side, &ChatEvent) + Send + Sync + 'static,
+    {
+        self.observer = Some(Box::new(observer));
+        self
+    }
+
+    /// Gets the session of one of the strangers
+    pub fn session(&self, side: Side) -> &ChatSession {
+        &self.sessions[side.index()]
+    }
+
+    /// Sends a message of your own to a stranger, bypassing the filter
+    ///
+    /// Can be called while the relay is running
+    ///
+    /// # Errors
+    /// This function fails if:
+    /// - The omegle server cannot be reached
+    /// - The response from omegle was malformed or indicated an error, like when
+    ///   the stranger isn't connected yet
+    pub async fn inject<M: ToString>(&self, to: Side, message: M) -> Result<(), OmegleLibError> {
+        self.session(to).send_message(message).await
+    }
+
+    /// Relays the chat until a stranger leaves, returning the one that left
+    ///
+    /// # Errors
+    /// This function fails if:
+    /// - The omegle server cannot be reached
+    /// - The response from omegle was malformed or indicated an error
+    ///
+    /// The relay stops at the first error, leaving both sessions as they are
+    pub async fn run(&self) -> Result<Side, OmegleLibError> {
+        let strangers = Mutex::new([Stranger::default(), Stranger::default()]);
+
+        let first = Box::pin(self.forward(Side::First, &strangers));
+        let second = Box::pin(self.forward(Side::Second, &strangers));
+        let (Either::Left((resp, _)) | Either::Right((resp, _))) =
+            future::select(first, second).await;
+        resp
+    }
+
+    /// Forwards the events of the stranger on side `from` to the other one until the chat ends
+    async fn forward(
+        &self,
+        from: Side,
+        strangers: &Mutex<[Stranger; 2]>,
+    ) -> Result<Side, OmegleLibError> {
+        let source = self.session(from);
+        let target = self.session(from.other());
+        let target_connected =
+            || strangers.lock().expect("lock is not poisoned")[from.other().index()].connected;
+
+        loop {
+            for event in source.get_events().await? {
+                if let Some(observer) = &self.observer {
+                    observer(from, &event);
+                }
+
+                match event {
+                    ChatEvent::Connected => {
+                        let held_back = {
+                            let mut strangers = strangers.lock().expect("lock is not poisoned");
+                            let stranger = &mut strangers[from.index()];
+                            stranger.connected = true;
+                            std::mem::take(&mut stranger.held_back)
+                        };
+                        for message in held_back {
+                            source.send_message(message).await?;
+                        }
+                    }
+                    ChatEvent::Message(message) => {
+                        let Some(message) = (match &self.filter {
+                            Some(filter) => filter(from, &message),
+                            None => Some(message),
+                        }) else {
+                            continue;
+                        };
+
+                        let send_now = {
+                            let mut strangers = strangers.lock().expect("lock is not poisoned");
+                            let stranger = &mut strangers[from.other().index()];
+                            if !stranger.connected {
+                                stranger.held_back.push(message.clone());
+                            }
+                            stranger.connected
+                        };
+                        if send_now {
+                            target.send_message(message).await?;
+                        }
+                    }
+                    ChatEvent::StartedTyping if target_connected() => target.start_typing().await?,
+                    ChatEvent::StoppedTyping if target_connected() => target.stop_typing().await?,
+                    event if event.ends_chat() => {
+                        target.disconnect().await?;
+                        return Ok(from);
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use futures::executor::block_on;
+
+    use crate::chat_session::tests::test_session;
+    use crate::transport::{MemoryTransport, RecordedRequest};
+
+    use super::*;
+
+    fn endpoint(request: &RecordedRequest) -> &str {
+        request.url.rsplit('/').next().unwrap()
+    }
+
+    fn actions(transport: &MemoryTransport) -> Vec<String> {
+        transport
+            .requests()
+            .iter()
+            .filter(|request| endpoint(request) != "events")
+            .map(|request| match request.form.get(1) {
+                Some((_, value)) => format!("{} {value}", endpoint(request)),
+                None => endpoint(request).to_string(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn forwards_between_strangers() {
+        let first = Arc::new(MemoryTransport::new());
+        first.push_response(
+            "/events",
+            r#"[["connected"], ["typing"], ["gotMessage", "hi"]]"#,
+        );
+        first.push_stall("/events");
+        first.push_response("/send", "win");
+        first.push_response("/disconnect", "win");
+        let second = Arc::new(MemoryTransport::new());
+        second.push_response("/events", r#"[["connected"]]"#);
+        second.push_response(
+            "/events",
+            r#"[["gotMessage", "hey"], ["strangerDisconnected"]]"#,
+        );
+        second.push_response("/send", "win");
+
+        let relay = Relay::new(test_session(first.clone()), test_session(second.clone()));
+        let left = block_on(relay.run()).unwrap();
+
+        assert_eq!(left, Side::Second);
+        // The second stranger wasn't connected yet when the first one typed and wrote
+        assert_eq!(actions(&second), vec!["send hi"]);
+        assert_eq!(actions(&first), vec!["send hey", "disconnect"]);
+    }
+
+    #[test]
+    fn filters_messages_and_reports_events() {
+        let first = Arc::new(MemoryTransport::new());
+        first.push_response("/events", r#"[["connected"]]"#);
+        first.push_stall("/events");
+        first.push_response("/send", "win");
+        first.push_response("/disconnect", "win");
+        let second = Arc::new(MemoryTransport::new());
+        second.push_response("/events", r#"[["connected"]]"#);
+        second.push_response(
+            "/events",
+            r#"[["gotMessage", "drop me"], ["gotMessage", "hello"], ["connectionDied"]]"#,
+        );
+
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let recorder = seen.clone();
+        let relay = Relay::new(test_session(first.clone()), test_session(second))
+            .filter(|_, message| (message != "drop me").then(|| message.to_uppercase()))
+            .observe(move |from, event| {
+                recorder.lock().unwrap().push(format!("{from:?} {event:?}"));
+            });
+        let left = block_on(relay.run()).unwrap();
+
+        assert_eq!(left, Side::Second);
+        assert_eq!(actions(&first), vec!["send HELLO", "disconnect"]);
+        assert_eq!(seen.lock().unwrap().len(), 5);
+    }
+
+    #[test]
+    fn injects_without_filtering() {
+        let transport = Arc::new(MemoryTransport::new());
+        transport.push_response("/send", "win");
+        let relay = Relay::new(
+            test_session(Arc::new(MemoryTransport::new())),
+            test_session(transport.clone()),
+        )
+        .filter(|_, _| None);
+
+        block_on(relay.inject(Side::Second, "psst")).unwrap();
+
+        assert_eq!(actions(&transport), vec!["send psst"]);
+    }
+}
@@ -0,0 +1,133 @@
+use std::convert::Infallible;
+use std::net::SocketAddr;
+
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Request, Response, StatusCode};
+
+use crate::server::{Reply, Server};
+use crate::types::error::OmegleLibError;
+
+impl Server {
+    /// Serves the protocol over HTTP on `addr` until the server fails
+    ///
+    /// # Errors
+    /// This function fails if:
+    /// - `addr` cannot be bound
+    /// - Accepting connections failed
+    pub async fn serve(self, addr: SocketAddr) -> Result<(), OmegleLibError> {
+        let make_service = make_service_fn(move |_| {
+            let server = self.clone();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |request| {
+                    let server = server.clone();
+                    async move { Ok::<_, Infallible>(server.respond(request).await) }
+                }))
+            }
+        });
+        hyper::Server::try_bind(&addr)?.serve(make_service).await?;
+        Ok(())
+    }
+
+    async fn respond(&self, request: Request<Body>) -> Response<Body> {
+        let path = request.uri().path().to_string();
+        let mut params = request
+            .uri()
+            .query()
+            .and_then(|query| serde_urlencoded::from_str::<Vec<(String, String)>>(query).ok())
+            .unwrap_or_default();
+        match hyper::body::to_bytes(request.into_body()).await {
+            Ok(body) => match serde_urlencoded::from_bytes::<Vec<(String, String)>>(&body) {
+                Ok(form) => params.extend(form),
+                Err(_) => return reply(StatusCode::BAD_REQUEST, "invalid form"),
+            },
+            Err(_) => return reply(StatusCode::BAD_REQUEST, "could not read body"),
+        }
+
+        match self.handle(&path, &params).await {
+            Reply::Ok(body) => reply(StatusCode::OK, body),
+            Reply::BadRequest(reason) => reply(StatusCode::BAD_REQUEST, reason),
+            Reply::NotFound => reply(StatusCode::NOT_FOUND, "not found"),
+        }
+    }
+}
+
+fn reply<B: Into<Body>>(status: StatusCode, body: B) -> Response<Body> {
+    let mut response = Response::new(body.into());
+    *response.status_mut() = status;
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::sync::Arc;
+
+    use crate::omegle::Omegle;
+    use crate::status::OmegleStatus;
+    use crate::transport::ReqwestTransport;
+    use crate::types::{chat_event::ChatEvent, endpoints::Endpoints, lang::LangCode};
+
+    use super::*;
+
+    async fn body_text(response: Response<Body>) -> String {
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        String::from_utf8(body.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn reads_query_and_form() {
+        let server = Server::new();
+        let start = Request::post("/start?firstevents=1&lang=fr&topics=%5B%22books%22%5D")
+            .body(Body::empty())
+            .unwrap();
+        let response = server.respond(start).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let start: serde_json::Value = serde_json::from_str(&body_text(response).await).unwrap();
+        assert_eq!(start["events"], serde_json::json!([["waiting"]]));
+
+        let send = Request::post("/send")
+            .body(Body::from(format!(
+                "id={}&msg=hi",
+                start["clientID"].as_str().unwrap().replace(':', "%3A")
+            )))
+            .unwrap();
+        assert_eq!(body_text(server.respond(send).await).await, "fail");
+
+        let missing = Request::get("/nowhere").body(Body::empty()).unwrap();
+        assert_eq!(
+            server.respond(missing).await.status(),
+            StatusCode::NOT_FOUND
+        )
+    }
+
+    #[tokio::test]
+    async fn serves_single_host_clients() {
+        // Borrow a free port from the OS
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        tokio::spawn(Server::new().serve(([127, 0, 0, 1], port).into()));
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+
+        let endpoints = Endpoints::single_host("127.0.0.1", port);
+        let transport = ReqwestTransport::default();
+        let status = OmegleStatus::get_omegle_status_from(&transport, &endpoints)
+            .await
+            .unwrap();
+        let mut omegle = Omegle::with_transport(
+            status,
+            HashSet::new(),
+            LangCode::English,
+            Arc::new(transport),
+        );
+        omegle.update_endpoints(endpoints);
+
+        let session = omegle.new_chat().await.unwrap();
+        assert_eq!(
+            session.get_events().await.unwrap().into_vec(),
+            vec![ChatEvent::Waiting]
+        )
+    }
+}
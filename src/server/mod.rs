@@ -0,0 +1,638 @@
+//! Omegle compatible chat server, requires the `server` feature
+//!
+//! [`Server`] implements the parts of omegle's HTTP protocol this crate uses
+//! (`/status`, `/check`, `/start`, `/events`, `/send`, `/typing`, `/stoppedtyping`
+//! and `/disconnect`) and pairs strangers with a [`Matchmaker`]. Serve it over HTTP
+//! with [`Server::serve`] and point clients at it with
+//! [`Endpoints::single_host`](crate::types::endpoints::Endpoints::single_host). The server also
+//! implements [`Transport`] so clients can talk to it without a network in tests.
+//!
+//! Every server and subdomain is answered on the same address, `Host` is ignored.
+//! Like omegle, clients that stop polling for events are disconnected after
+//! [`CLIENT_TIMEOUT`].
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use reqwest::Url;
+use serde_json::json;
+use tokio::sync::Notify;
+use tokio::time::Instant;
+use vec1::vec1;
+
+use crate::matchmaking::{Match, Matchmaker};
+use crate::omegle::normalize_interest;
+use crate::status::OmegleStatus;
+use crate::transport::Transport;
+use crate::types::chat_event::ChatEvent;
+use crate::types::chat_server::ChatServer;
+use crate::types::check_server::CheckServer;
+use crate::types::client_id::{ClientID, ServerType};
+use crate::types::error::OmegleLibError;
+use crate::types::lang::LangCode;
+
+mod http;
+
+/// Response to an action that went through
+const SUCCESS: &str = "win";
+/// Response to an action from a client that isn't chatting
+const FAILURE: &str = "fail";
+
+/// How long a client can go without polling for events before it is disconnected
+pub const CLIENT_TIMEOUT: Duration = Duration::from_secs(30);
+/// How long a request for events waits before ending with an empty list of events
+pub const LONG_POLL_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// What the server answers a request with
+#[derive(Debug, PartialEq)]
+pub(crate) enum Reply {
+    Ok(String),
+    BadRequest(String),
+    NotFound,
+}
+
+#[derive(Debug)]
+struct Client {
+    partner: Option<String>,
+    /// Events waiting for the client's next `/events` request
+    events: Vec<ChatEvent>,
+    notify: Arc<Notify>,
+    /// Whether the chat is over, the client is removed once it got its last events
+    ended: bool,
+    /// When the client last started or finished polling for events
+    last_seen: Instant,
+    /// Amount of `/events` requests of the client that are still pending
+    polls: usize,
+}
+
+impl Client {
+    fn push(&mut self, event: ChatEvent) {
+        self.events.push(event);
+        self.notify.notify_one();
+    }
+}
+
+#[derive(Debug, Default)]
+struct State {
+    clients: HashMap<String, Client>,
+    matchmaker: Matchmaker<String>,
+}
+
+impl State {
+    /// Tells both clients of a match they are now chatting
+    fn connect(&mut self, found: Match<String>) {
+        for (id, partner) in [(&found.first, &found.second), (&found.second, &found.first)] {
+            let client = self.clients.get_mut(id).expect("matched clients are known");
+            client.partner = Some(partner.clone());
+            for event in found.events() {
+                client.push(event);
+            }
+        }
+    }
+
+    /// Ends the chat of `id`, telling their partner if they had one
+    fn disconnect(&mut self, id: &str) -> bool {
+        let Some(client) = self.clients.remove(id) else {
+            return false;
+        };
+        self.matchmaker.remove(&id.to_string());
+        if let Some(partner) = client
+            .partner
+            .and_then(|partner| self.clients.get_mut(&partner))
+        {
+            partner.partner = None;
+            partner.ended = true;
+            partner.push(ChatEvent::Disconnected);
+        }
+        // Wakes up a pending `/events` request so it can see the client is gone
+        client.notify.notify_one();
+        true
+    }
+
+    /// Disconnects the clients that stopped polling for events
+    fn expire(&mut self, now: Instant) {
+        let expired = self
+            .clients
+            .iter()
+            .filter(|(_, client)| {
+                client.polls == 0
+                    && now.saturating_duration_since(client.last_seen) >= CLIENT_TIMEOUT
+            })
+            .map(|(id, _)| id.clone())
+            .collect::<Vec<_>>();
+        for id in expired {
+            self.disconnect(&id);
+        }
+    }
+}
+
+/// Keeps a client from expiring while one of its `/events` requests is pending
+struct PendingPoll {
+    state: Arc<Mutex<State>>,
+    id: String,
+}
+
+impl Drop for PendingPoll {
+    fn drop(&mut self) {
+        let mut state = self.state.lock().expect("lock is not poisoned");
+        if let Some(client) = state.clients.get_mut(&self.id) {
+            client.polls -= 1;
+            client.last_seen = Instant::now();
+        }
+    }
+}
+
+/// A self-hostable omegle compatible chat server
+///
+/// # Examples
+/// Serve on port 8080 and chat through it
+/// ```rust
+/// use omegle_rs::omegle::Omegle;
+/// use omegle_rs::server::Server;
+/// use omegle_rs::status::OmegleStatus;
+/// use omegle_rs::transport::ReqwestTransport;
+/// use omegle_rs::types::endpoints::Endpoints;
+/// use omegle_rs::types::lang::LangCode;
+/// use std::collections::HashSet;
+/// use std::sync::Arc;
+///
+/// async fn run() {
+///     tokio::spawn(Server::new().serve(([0, 0, 0, 0], 8080).into()));
+///
+///     let endpoints = Endpoints::single_host("127.0.0.1", 8080);
+///     let transport = ReqwestTransport::default();
+///     let status = OmegleStatus::get_omegle_status_from(&transport, &endpoints)
+///         .await
+///         .unwrap();
+///     let mut omegle = Omegle::with_transport(
+///         status,
+///         HashSet::new(),
+///         LangCode::English,
+///         Arc::new(transport),
+///     );
+///     omegle.update_endpoints(endpoints);
+///     let session = omegle.new_chat().await.unwrap();
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Server {
+    state: Arc<Mutex<State>>,
+}
+
+impl Server {
+    /// Creates a new [`Server`] without any clients
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets how long clients with interests wait for a shared interest before being
+    /// paired with anyone, see [`Matchmaker::fallback_after`]
+    pub fn fallback_after(self, fallback_after: Option<Duration>) -> Self {
+        {
+            let mut state = self.state.lock().expect("lock is not poisoned");
+            state.matchmaker = std::mem::take(&mut state.matchmaker).fallback_after(fallback_after);
+        }
+        self
+    }
+
+    /// Gets how many clients are chatting or looking for a stranger
+    pub fn get_count(&self) -> usize {
+        self.state
+            .lock()
+            .expect("lock is not poisoned")
+            .clients
+            .len()
+    }
+
+    /// Answers a request to `path`, `params` are the query and form fields
+    pub(crate) async fn handle(&self, path: &str, params: &[(String, String)]) -> Reply {
+        let param = |name: &str| {
+            params
+                .iter()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.as_str())
+        };
+        let id = param("id").unwrap_or_default();
+        self.state
+            .lock()
+            .expect("lock is not poisoned")
+            .expire(Instant::now());
+
+        match path {
+            "/status" => Reply::Ok(self.status()),
+            "/check" => Reply::Ok(random_string(24)),
+            "/start" => self.start(
+                param("lang"),
+                param("topics"),
+                param("firstevents") == Some("1"),
+            ),
+            "/events" => self.events(id).await,
+            "/send" => match param("msg") {
+                Some(message) => self.forward(id, ChatEvent::Message(message.to_string())),
+                None => Reply::BadRequest(String::from("missing 'msg'")),
+            },
+            "/typing" => self.forward(id, ChatEvent::StartedTyping),
+            "/stoppedtyping" => self.forward(id, ChatEvent::StoppedTyping),
+            "/disconnect" => {
+                let disconnected = self
+                    .state
+                    .lock()
+                    .expect("lock is not poisoned")
+                    .disconnect(id);
+                Reply::Ok(String::from(if disconnected { SUCCESS } else { FAILURE }))
+            }
+            _ => Reply::NotFound,
+        }
+    }
+
+    fn status(&self) -> String {
+        let status = OmegleStatus {
+            count: self.get_count() as u64,
+            servers: vec1![ChatServer::from(1)],
+            antinudeservers: vec1![CheckServer(1)],
+            rtmfp: None,
+            spy_queue_time: None,
+            spyee_queue_time: None,
+        };
+        serde_json::to_string(&status).expect("status is valid JSON")
+    }
+
+    fn start(&self, lang: Option<&str>, topics: Option<&str>, first_events: bool) -> Reply {
+        let lang = match lang.map(str::parse::<LangCode>).transpose() {
+            Ok(lang) => lang.unwrap_or(LangCode::English),
+            Err(_) => return Reply::BadRequest(String::from("unsupported language")),
+        };
+        let topics = match topics.map(serde_json::from_str::<Vec<String>>).transpose() {
+            Ok(topics) => topics
+                .unwrap_or_default()
+                .iter()
+                .filter_map(|topic| normalize_interest(topic).ok())
+                .collect::<Vec<_>>(),
+            Err(_) => return Reply::BadRequest(String::from("topics must be a JSON array")),
+        };
+
+        let id = new_client_id();
+        let mut state = self.state.lock().expect("lock is not poisoned");
+        state.clients.insert(
+            id.clone(),
+            Client {
+                partner: None,
+                events: Vec::new(),
+                notify: Arc::new(Notify::new()),
+                ended: false,
+                last_seen: Instant::now(),
+                polls: 0,
+            },
+        );
+        match state
+            .matchmaker
+            .enqueue(id.clone(), lang, topics, Instant::now())
+        {
+            Some(found) => state.connect(found),
+            None => state
+                .clients
+                .get_mut(&id)
+                .expect("client was just added")
+                .push(ChatEvent::Waiting),
+        }
+
+        let body = if first_events {
+            let client = state.clients.get_mut(&id).expect("client was just added");
+            let events = std::mem::take(&mut client.events);
+            json!({ "clientID": id, "events": events }).to_string()
+        } else {
+            json!(id).to_string()
+        };
+        Reply::Ok(body)
+    }
+
+    /// Waits until there are events for `id` and hands them out
+    ///
+    /// Clients that are unknown or expired are told the stranger disconnected, and
+    /// the wait ends with an empty list after [`LONG_POLL_TIMEOUT`]
+    async fn events(&self, id: &str) -> Reply {
+        let poll_deadline = Instant::now() + LONG_POLL_TIMEOUT;
+        let Some(_poll) = self.start_poll(id) else {
+            return Reply::Ok(events_json(&[ChatEvent::Disconnected]));
+        };
+        loop {
+            let (notify, match_deadline) = {
+                let mut state = self.state.lock().expect("lock is not poisoned");
+                let Some(client) = state.clients.get_mut(id) else {
+                    return Reply::Ok(events_json(&[ChatEvent::Disconnected]));
+                };
+                if !client.events.is_empty() {
+                    let events = std::mem::take(&mut client.events);
+                    if client.ended {
+                        state.clients.remove(id);
+                    }
+                    return Reply::Ok(events_json(&events));
+                }
+                (
+                    client.notify.clone(),
+                    state.matchmaker.next_deadline(Instant::now()),
+                )
+            };
+            // Interest searches can time out while nobody else starts a chat
+            let deadline =
+                match_deadline.map_or(poll_deadline, |deadline| deadline.min(poll_deadline));
+            if tokio::time::timeout_at(deadline, notify.notified())
+                .await
+                .is_err()
+            {
+                if Instant::now() >= poll_deadline {
+                    return Reply::Ok(events_json(&[]));
+                }
+                self.match_timed_out();
+            }
+        }
+    }
+
+    /// Marks a request for the events of `id` as pending, if the client is known
+    fn start_poll(&self, id: &str) -> Option<PendingPoll> {
+        let mut state = self.state.lock().expect("lock is not poisoned");
+        let client = state.clients.get_mut(id)?;
+        client.polls += 1;
+        client.last_seen = Instant::now();
+        Some(PendingPoll {
+            state: self.state.clone(),
+            id: id.to_string(),
+        })
+    }
+
+    /// Pairs the clients whose interest search timed out
+    fn match_timed_out(&self) {
+        let mut state = self.state.lock().expect("lock is not poisoned");
+        for found in state.matchmaker.poll(Instant::now()) {
+            state.connect(found);
+        }
+    }
+
+    /// Hands `event` to the partner of `id`
+    fn forward(&self, id: &str, event: ChatEvent) -> Reply {
+        let mut state = self.state.lock().expect("lock is not poisoned");
+        let partner = state
+            .clients
+            .get(id)
+            .and_then(|client| client.partner.clone());
+        match partner.and_then(|partner| state.clients.get_mut(&partner)) {
+            Some(partner) => {
+                partner.push(event);
+                Reply::Ok(String::from(SUCCESS))
+            }
+            None => Reply::Ok(String::from(FAILURE)),
+        }
+    }
+}
+
+#[async_trait]
+impl Transport for Server {
+    async fn post_form(&self, url: &str, form: &[(&str, &str)]) -> Result<String, OmegleLibError> {
+        let url = Url::parse(url)
+            .map_err(|err| OmegleLibError::TransportError(format!("invalid url '{url}': {err}")))?;
+        let params = url
+            .query_pairs()
+            .map(|(key, value)| (key.into_owned(), value.into_owned()))
+            .chain(
+                form.iter()
+                    .map(|(key, value)| (key.to_string(), value.to_string())),
+            )
+            .collect::<Vec<_>>();
+        match self.handle(url.path(), &params).await {
+            Reply::Ok(body) => Ok(body),
+            Reply::BadRequest(reason) => Err(OmegleLibError::TransportError(reason)),
+            Reply::NotFound => Err(OmegleLibError::TransportError(format!(
+                "no endpoint '{}'",
+                url.path()
+            ))),
+        }
+    }
+
+    async fn get(&self, url: &str) -> Result<String, OmegleLibError> {
+        self.post_form(url, &[]).await
+    }
+}
+
+fn events_json(events: &[ChatEvent]) -> String {
+    serde_json::to_string(events).expect("events are valid JSON")
+}
+
+fn random_string(length: usize) -> String {
+    rand::thread_rng()
+        .sample_iter(Alphanumeric)
+        .take(length)
+        .map(char::from)
+        .collect()
+}
+
+fn new_client_id() -> String {
+    let mut user_id = [' '; 30];
+    for (slot, char) in user_id.iter_mut().zip(random_string(30).chars()) {
+        *slot = char;
+    }
+    String::from(ClientID {
+        server_type: ServerType::Central,
+        server_id: 1,
+        user_id,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::chat_session::ChatSession;
+    use crate::omegle::Omegle;
+    use crate::types::endpoints::Endpoints;
+
+    use super::*;
+
+    async fn new_omegle(server: &Server, lang: LangCode, interests: &[&str]) -> Omegle {
+        let endpoints = Endpoints::single_host("127.0.0.1", 8080);
+        let status = OmegleStatus::get_omegle_status_from(server, &endpoints)
+            .await
+            .unwrap();
+        let mut omegle = Omegle::with_transport(
+            status,
+            interests
+                .iter()
+                .map(|interest| interest.to_string())
+                .collect(),
+            lang,
+            Arc::new(server.clone()),
+        );
+        omegle.update_endpoints(endpoints);
+        omegle
+    }
+
+    async fn next_events(session: &ChatSession) -> Vec<ChatEvent> {
+        tokio::time::timeout(Duration::from_secs(1), session.get_events())
+            .await
+            .expect("events arrive")
+            .unwrap()
+            .into_vec()
+    }
+
+    #[tokio::test]
+    async fn pairs_clients_and_relays_messages() {
+        let server = Server::new();
+        let first = new_omegle(&server, LangCode::English, &["music", "books"])
+            .await
+            .new_chat()
+            .await
+            .unwrap();
+        let second = new_omegle(&server, LangCode::English, &["Books"])
+            .await
+            .new_chat()
+            .await
+            .unwrap();
+
+        assert_eq!(next_events(&first).await[0], ChatEvent::Waiting);
+        assert_eq!(
+            next_events(&first).await,
+            vec![
+                ChatEvent::Connected,
+                ChatEvent::CommonLikes(vec1![String::from("books")])
+            ]
+        );
+        assert_eq!(
+            next_events(&second).await,
+            vec![
+                ChatEvent::Connected,
+                ChatEvent::CommonLikes(vec1![String::from("books")])
+            ]
+        );
+
+        second.start_typing().await.unwrap();
+        second.send_message("hi").await.unwrap();
+        assert_eq!(
+            next_events(&first).await,
+            vec![
+                ChatEvent::StartedTyping,
+                ChatEvent::Message(String::from("hi"))
+            ]
+        );
+
+        first.disconnect().await.unwrap();
+        assert_eq!(next_events(&second).await, vec![ChatEvent::Disconnected]);
+        assert_eq!(server.get_count(), 0)
+    }
+
+    #[tokio::test]
+    async fn only_pairs_same_language_and_shared_interests() {
+        let server = Server::new();
+        let english = new_omegle(&server, LangCode::English, &[]).await;
+        let french = new_omegle(&server, LangCode::French, &[]).await;
+        let knitting = new_omegle(&server, LangCode::English, &["knitting"]).await;
+
+        let first = english.new_chat().await.unwrap();
+        let _french = french.new_chat().await.unwrap();
+        let _knitting = knitting.new_chat().await.unwrap();
+        let second = english.new_chat().await.unwrap();
+
+        assert_eq!(next_events(&first).await, vec![ChatEvent::Waiting]);
+        assert_eq!(next_events(&first).await, vec![ChatEvent::Connected]);
+        assert_eq!(next_events(&second).await, vec![ChatEvent::Connected]);
+        assert_eq!(server.get_count(), 4)
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn falls_back_to_random_strangers() {
+        let server = Server::new().fallback_after(Some(Duration::from_secs(5)));
+        let knitting = new_omegle(&server, LangCode::English, &["knitting"])
+            .await
+            .new_chat()
+            .await
+            .unwrap();
+        let random = new_omegle(&server, LangCode::English, &[])
+            .await
+            .new_chat()
+            .await
+            .unwrap();
+        assert_eq!(next_events(&knitting).await, vec![ChatEvent::Waiting]);
+        assert_eq!(next_events(&random).await, vec![ChatEvent::Waiting]);
+
+        let start = Instant::now();
+        let events = tokio::time::timeout(Duration::from_secs(10), knitting.get_events())
+            .await
+            .expect("the interest search times out")
+            .unwrap();
+
+        assert_eq!(events.into_vec(), vec![ChatEvent::Connected]);
+        assert_eq!(start.elapsed(), Duration::from_secs(5));
+        assert_eq!(next_events(&random).await, vec![ChatEvent::Connected])
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn expired_clients_are_disconnected() {
+        let server = Server::new();
+        let omegle = new_omegle(&server, LangCode::English, &[]).await;
+        let first = omegle.new_chat().await.unwrap();
+        let second = omegle.new_chat().await.unwrap();
+        assert_eq!(next_events(&first).await, vec![ChatEvent::Waiting]);
+        assert_eq!(next_events(&first).await, vec![ChatEvent::Connected]);
+        assert_eq!(next_events(&second).await, vec![ChatEvent::Connected]);
+
+        // Only the first client keeps polling, through long-polls that end empty
+        let start = Instant::now();
+        let events = first.get_events().await.unwrap();
+
+        assert_eq!(events.into_vec(), vec![ChatEvent::Disconnected]);
+        assert_eq!(start.elapsed(), LONG_POLL_TIMEOUT * 2);
+        assert_eq!(server.get_count(), 0);
+        assert_eq!(
+            second.get_events().await.unwrap().into_vec(),
+            vec![ChatEvent::Disconnected]
+        )
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn long_polls_end_empty() {
+        let server = Server::new();
+        let Reply::Ok(id) = server.handle("/start", &[]).await else {
+            panic!("chat starts")
+        };
+        let id = vec![(
+            String::from("id"),
+            serde_json::from_str::<String>(&id).unwrap(),
+        )];
+        assert_eq!(
+            server.handle("/events", &id).await,
+            Reply::Ok(String::from(r#"[["waiting"]]"#))
+        );
+
+        let start = Instant::now();
+        assert_eq!(
+            server.handle("/events", &id).await,
+            Reply::Ok(String::from("[]"))
+        );
+        assert_eq!(start.elapsed(), LONG_POLL_TIMEOUT);
+        assert_eq!(server.get_count(), 1)
+    }
+
+    #[tokio::test]
+    async fn rejects_actions_outside_of_chats() {
+        let server = Server::new();
+        let session = new_omegle(&server, LangCode::English, &[])
+            .await
+            .new_chat()
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            session.send_message("anyone?").await,
+            Err(OmegleLibError::OmegleError(resp)) if resp == FAILURE
+        ));
+        assert_eq!(server.handle("/nowhere", &[]).await, Reply::NotFound);
+        assert!(matches!(
+            server
+                .handle("/start", &[(String::from("lang"), String::from("xx"))])
+                .await,
+            Reply::BadRequest(_)
+        ))
+    }
+}
@@ -0,0 +1,420 @@
+//! Running many [`ChatSession`]s at once
+//!
+//! A [`SessionManager`] owns the sessions added to it and polls their events with a
+//! bounded amount of requests in flight, handing every event out through a single stream.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use futures::future::Either;
+use futures::Stream;
+use tokio::sync::mpsc::{self, Receiver, Sender};
+use tokio::sync::{Notify, Semaphore};
+use tokio::task::JoinHandle;
+
+use crate::chat_session::ChatSession;
+use crate::types::{chat_event::ChatEvent, error::OmegleLibError};
+
+/// Identifier of a session owned by a [`SessionManager`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SessionId(u64);
+
+impl fmt::Display for SessionId {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(formatter, "{}", self.0)
+    }
+}
+
+/// An owned session and the task polling it
+struct Managed {
+    session: ChatSession,
+    task: JoinHandle<()>,
+}
+
+type EventResult = (SessionId, Result<ChatEvent, OmegleLibError>);
+
+/// How many events can wait to be handed out before the sessions stop being polled
+const EVENT_BUFFER: usize = 256;
+
+/// State shared between the manager and the tasks polling its sessions
+#[derive(Default)]
+struct Shared {
+    sessions: Mutex<HashMap<SessionId, Managed>>,
+    /// Notified when the last session is dropped, so the stream of events can end
+    emptied: Notify,
+}
+
+impl Shared {
+    fn lock_sessions(&self) -> std::sync::MutexGuard<'_, HashMap<SessionId, Managed>> {
+        self.sessions.lock().expect("lock is not poisoned")
+    }
+
+    /// Drops a session, returning it if the manager still owned it
+    fn remove(&self, id: SessionId) -> Option<Managed> {
+        let mut sessions = self.lock_sessions();
+        let managed = sessions.remove(&id);
+        if managed.is_some() && sessions.is_empty() {
+            self.emptied.notify_one();
+        }
+        managed
+    }
+}
+
+/// Struct owning many [`ChatSession`]s and polling their events concurrently
+///
+/// Every session gets its own task, but at most `workers` of them wait on a request
+/// for events at once. Events are buffered up to a limit, after which sessions stop
+/// being polled until the events are consumed. A session is dropped from the manager
+/// once one of its events [ends the chat](ChatEvent::ends_chat). If polling a session
+/// fails for any reason other than a [timeout](OmegleLibError::Timeout), the error is
+/// emitted for it and it is dropped as well. The stream of events ends once every
+/// session was dropped.
+///
+/// # Examples
+/// ```rust
+/// use futures::StreamExt;
+/// use omegle_rs::omegle::Omegle;
+/// use omegle_rs::session_manager::SessionManager;
+///
+/// async fn run(omegle: Omegle) {
+///     let mut manager = SessionManager::new(16);
+///     for _ in 0..10 {
+///         manager.add(omegle.new_chat().await.unwrap());
+///     }
+///     while let Some((id, event)) = manager.events().next().await {
+///         match event {
+///             Ok(event) => println!("{id}: {event:?}"),
+///             Err(error) => println!("{id} failed: {error}"),
+///         }
+///     }
+/// }
+/// ```
+pub struct SessionManager {
+    shared: Arc<Shared>,
+    polls: Arc<Semaphore>,
+    next_id: AtomicU64,
+    events_tx: Sender<EventResult>,
+    events_rx: Receiver<EventResult>,
+}
+
+impl SessionManager {
+    /// Creates a new [`SessionManager`] polling at most `workers` sessions at once
+    ///
+    /// # Panics
+    /// Panics if `workers` is 0
+    pub fn new(workers: usize) -> Self {
+        assert!(workers > 0, "a session manager needs at least one worker");
+
+        let (events_tx, events_rx) = mpsc::channel(EVENT_BUFFER);
+        Self {
+            shared: Arc::default(),
+            polls: Arc::new(Semaphore::new(workers)),
+            next_id: AtomicU64::new(0),
+            events_tx,
+            events_rx,
+        }
+    }
+
+    /// Adds a session to the manager and starts polling its events
+    ///
+    /// # Panics
+    /// Panics if called outside of a tokio runtime
+    pub fn add(&self, session: ChatSession) -> SessionId {
+        let id = SessionId(self.next_id.fetch_add(1, Ordering::Relaxed));
+        // The task removes the session once its chat ends, so it is inserted before
+        // the lock is released
+        let mut sessions = self.lock_sessions();
+        let task = tokio::spawn(poll_session(
+            id,
+            session.clone(),
+            self.shared.clone(),
+            self.polls.clone(),
+            self.events_tx.clone(),
+        ));
+        sessions.insert(id, Managed { session, task });
+        id
+    }
+
+    /// Gets a handle to a session, if it is still owned by the manager
+    pub fn get(&self, id: SessionId) -> Option<ChatSession> {
+        self.lock_sessions()
+            .get(&id)
+            .map(|managed| managed.session.clone())
+    }
+
+    /// Gets the ids of every session still owned by the manager
+    pub fn ids(&self) -> Vec<SessionId> {
+        self.lock_sessions().keys().copied().collect()
+    }
+
+    /// Gets the amount of sessions still owned by the manager
+    pub fn len(&self) -> usize {
+        self.lock_sessions().len()
+    }
+
+    /// Gets whether the manager owns no sessions
+    pub fn is_empty(&self) -> bool {
+        self.lock_sessions().is_empty()
+    }
+
+    /// Sends a message in the session with the given id
+    ///
+    /// # Errors
+    /// This function fails if:
+    /// - No session with that id is owned by the manager
+    /// - Sending the message failed, see [`ChatSession::send_message`]
+    pub async fn send_message<M: ToString>(
+        &self,
+        id: SessionId,
+        message: M,
+    ) -> Result<(), OmegleLibError> {
+        self.session(id)?.send_message(message).await
+    }
+
+    /// Sends a typing indicator in the session with the given id
+    ///
+    /// # Errors
+    /// This function fails if:
+    /// - No session with that id is owned by the manager
+    /// - Sending the indicator failed, see [`ChatSession::start_typing`]
+    pub async fn start_typing(&self, id: SessionId) -> Result<(), OmegleLibError> {
+        self.session(id)?.start_typing().await
+    }
+
+    /// Sends a stopped typing indicator in the session with the given id
+    ///
+    /// # Errors
+    /// This function fails if:
+    /// - No session with that id is owned by the manager
+    /// - Sending the indicator failed, see [`ChatSession::stop_typing`]
+    pub async fn stop_typing(&self, id: SessionId) -> Result<(), OmegleLibError> {
+        self.session(id)?.stop_typing().await
+    }
+
+    /// Disconnects the session with the given id and drops it from the manager
+    ///
+    /// # Errors
+    /// This function fails if:
+    /// - No session with that id is owned by the manager
+    /// - Disconnecting failed, see [`ChatSession::disconnect`]
+    pub async fn disconnect(&self, id: SessionId) -> Result<(), OmegleLibError> {
+        let managed = self
+            .shared
+            .remove(id)
+            .ok_or(OmegleLibError::UnknownSession(id))?;
+        managed.task.abort();
+        managed.session.disconnect().await
+    }
+
+    /// Waits for the next event of any session
+    ///
+    /// Polling errors are returned in place of an event, after which the session is
+    /// dropped. Returns `None` once the manager owns no sessions and every event was
+    /// handed out
+    pub async fn next_event(&mut self) -> Option<EventResult> {
+        loop {
+            // Sessions are dropped after their last events were sent
+            if let Ok(event) = self.events_rx.try_recv() {
+                return Some(event);
+            }
+            if self.is_empty() {
+                return None;
+            }
+            let shared = self.shared.clone();
+            let emptied = std::pin::pin!(shared.emptied.notified());
+            let received = std::pin::pin!(self.events_rx.recv());
+            if let Either::Left((event, _)) = futures::future::select(received, emptied).await {
+                return event;
+            }
+        }
+    }
+
+    /// Gets a stream of the events of every session, tagged with the id of the session
+    ///
+    /// The stream ends once the manager owns no sessions, see [`SessionManager::next_event`]
+    pub fn events(&mut self) -> impl Stream<Item = EventResult> + Unpin + '_ {
+        Box::pin(futures::stream::unfold(self, |manager| async move {
+            let event = manager.next_event().await?;
+            Some((event, manager))
+        }))
+    }
+
+    fn session(&self, id: SessionId) -> Result<ChatSession, OmegleLibError> {
+        self.get(id).ok_or(OmegleLibError::UnknownSession(id))
+    }
+
+    fn lock_sessions(&self) -> std::sync::MutexGuard<'_, HashMap<SessionId, Managed>> {
+        self.shared.lock_sessions()
+    }
+}
+
+impl Drop for SessionManager {
+    fn drop(&mut self) {
+        for managed in self.lock_sessions().values() {
+            managed.task.abort();
+        }
+    }
+}
+
+/// Polls a session until its chat ends, polling failed or it was disconnected by the caller
+async fn poll_session(
+    id: SessionId,
+    session: ChatSession,
+    shared: Arc<Shared>,
+    polls: Arc<Semaphore>,
+    events_tx: Sender<EventResult>,
+) {
+    loop {
+        let result = {
+            let _permit = polls.acquire().await.expect("semaphore is never closed");
+            session.get_events().await
+        };
+        let (events, chat_ended) = match result {
+            Ok(events) => {
+                let chat_ended = events.iter().any(ChatEvent::ends_chat);
+                (events.into_iter().map(Ok).collect(), chat_ended)
+            }
+            Err(OmegleLibError::Timeout(_)) => (Vec::new(), false),
+            Err(error) => (vec![Err(error)], true),
+        };
+
+        if !shared.lock_sessions().contains_key(&id) {
+            // Disconnected by the caller while polling
+            return;
+        }
+        for event in events {
+            if events_tx.send((id, event)).await.is_err() {
+                return;
+            }
+        }
+        if chat_ended {
+            shared.remove(id);
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use futures::StreamExt;
+
+    use crate::chat_session::tests::test_session;
+    use crate::transport::MemoryTransport;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn merges_events_until_chats_end() {
+        let first = Arc::new(MemoryTransport::new());
+        first.push_response("/events", r#"[["connected"]]"#);
+        first.push_response("/events", r#"[["strangerDisconnected"]]"#);
+        let second = Arc::new(MemoryTransport::new());
+        second.push_response("/events", r#"[["gotMessage", "hi"], ["connectionDied"]]"#);
+
+        let mut manager = SessionManager::new(2);
+        let first_id = manager.add(test_session(first));
+        let second_id = manager.add(test_session(second));
+
+        // Ends once both chats ended
+        let mut events = manager
+            .events()
+            .map(|(id, event)| (id, event.unwrap()))
+            .collect::<Vec<_>>()
+            .await;
+        events.sort_by_key(|(id, _)| *id);
+
+        assert_eq!(
+            events,
+            vec![
+                (first_id, ChatEvent::Connected),
+                (first_id, ChatEvent::Disconnected),
+                (second_id, ChatEvent::Message(String::from("hi"))),
+                (second_id, ChatEvent::ConnectionDied),
+            ]
+        );
+        assert!(manager.is_empty())
+    }
+
+    #[tokio::test]
+    async fn reports_polling_errors_and_drops_session() {
+        let transport = Arc::new(MemoryTransport::new());
+        transport.push_response("/events", "null");
+        let mut manager = SessionManager::new(2);
+        let id = manager.add(test_session(transport));
+
+        let (event_id, event) = manager.next_event().await.unwrap();
+
+        assert_eq!(event_id, id);
+        assert!(matches!(event, Err(OmegleLibError::JsonError(_))));
+        assert!(manager.get(id).is_none())
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn idle_sessions_dont_hold_back_free_workers() {
+        let transports = (0..3)
+            .map(|_| Arc::new(MemoryTransport::new()))
+            .collect::<Vec<_>>();
+        for transport in &transports[..2] {
+            transport.push_stall("/events");
+        }
+        transports[2].push_response("/events", r#"[["connected"]]"#);
+        // One worker is left for the ready session
+        let mut manager = SessionManager::new(3);
+        let ids = transports
+            .iter()
+            .map(|transport| manager.add(test_session(transport.clone())))
+            .collect::<Vec<_>>();
+
+        let (id, event) = manager.next_event().await.unwrap();
+
+        assert_eq!(id, ids[2]);
+        assert_eq!(event.unwrap(), ChatEvent::Connected)
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn polls_at_most_workers_sessions_at_once() {
+        let transports = (0..3)
+            .map(|_| Arc::new(MemoryTransport::new()))
+            .collect::<Vec<_>>();
+        let manager = SessionManager::new(2);
+        for transport in &transports {
+            transport.push_stall("/events");
+            manager.add(test_session(transport.clone()));
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+
+        let polls = transports
+            .iter()
+            .map(|transport| transport.requests().len())
+            .sum::<usize>();
+        assert_eq!(polls, 2)
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn sends_and_disconnects_by_id() {
+        let transport = Arc::new(MemoryTransport::new());
+        transport.push_stall("/events");
+        transport.push_response("/send", "win");
+        transport.push_response("/disconnect", "win");
+        let manager = SessionManager::new(2);
+        let id = manager.add(test_session(transport.clone()));
+
+        manager.send_message(id, "hello").await.unwrap();
+        manager.disconnect(id).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(10)).await;
+
+        assert!(manager.is_empty());
+        assert!(matches!(
+            manager.send_message(id, "hello again").await,
+            Err(OmegleLibError::UnknownSession(unknown)) if unknown == id
+        ));
+        assert!(transport
+            .requests()
+            .iter()
+            .any(|request| request.url.ends_with("/disconnect")))
+    }
+}
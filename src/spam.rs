@@ -0,0 +1,298 @@
+//! Telling spam bots apart from humans
+//!
+//! Many strangers are bots that paste a link or an ad right after connecting,
+//! without ever typing. A [`SpamClassifier`] scores the [`Timeline`] of a chat
+//! based on those traits. Set it with
+//! [`ChatSession::update_spam_classifier`](crate::chat_session::ChatSession::update_spam_classifier)
+//! to have strangers checked after every message they send.
+
+use std::time::Duration;
+
+use regex::Regex;
+use tokio::time::Instant;
+
+use crate::filter::LINK_PATTERN;
+use crate::types::chat_event::ChatEvent;
+pub use crate::types::spam_score::{SpamScore, SpamSignal};
+
+/// Phrases commonly found in the messages of spam bots
+const DEFAULT_PHRASES: [&str; 8] = [
+    "add me on",
+    "check out my",
+    "check my profile",
+    "click here",
+    "onlyfans",
+    "snapchat",
+    "telegram",
+    "kik me",
+];
+
+/// The events of a chat that matter for spotting spam, starting when the stranger connected
+///
+/// Events polled together arrive at once, so a first message received with
+/// [`ChatEvent::Connected`] has an unknown delay and is never considered too fast
+#[derive(Debug, Clone, Default)]
+pub struct Timeline {
+    connected_at: Option<Instant>,
+    /// Whether the events being recorded were polled with the connection
+    connecting: bool,
+    first_message_at: Option<Instant>,
+    typed: bool,
+    messages: Vec<String>,
+    /// Whether the stranger was already reported as spam
+    pub(crate) flagged: bool,
+}
+
+impl Timeline {
+    /// Creates a new empty [`Timeline`]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records an event that just happened, a [`ChatEvent::Connected`] starts over
+    pub fn record(&mut self, event: &ChatEvent) {
+        self.record_at(event, Instant::now());
+        self.end_batch()
+    }
+
+    /// Marks the end of the events that were polled together
+    pub(crate) fn end_batch(&mut self) {
+        self.connecting = false
+    }
+
+    pub(crate) fn record_at(&mut self, event: &ChatEvent, at: Instant) {
+        match event {
+            ChatEvent::Connected => {
+                *self = Self {
+                    connected_at: Some(at),
+                    connecting: true,
+                    ..Self::default()
+                }
+            }
+            ChatEvent::StartedTyping => self.typed = true,
+            ChatEvent::Message(message) => {
+                if self.first_message_at.is_none() && self.connecting {
+                    // When the stranger connected between two polls is unknown
+                    self.connected_at = None;
+                }
+                self.first_message_at.get_or_insert(at);
+                self.messages.push(message.clone());
+            }
+            _ => {}
+        }
+    }
+
+    /// Gets the messages the stranger sent, oldest first
+    pub fn get_messages(&self) -> &[String] {
+        &self.messages
+    }
+}
+
+/// Heuristic classifier scoring how likely a stranger is to be a spam bot
+///
+/// # Examples
+/// Skip strangers that look like bots
+/// ```rust
+/// use omegle_rs::chat_session::ChatSession;
+/// use omegle_rs::spam::SpamClassifier;
+/// use std::time::Duration;
+///
+/// async fn run(mut session: ChatSession) {
+///     let classifier = SpamClassifier::new()
+///         .phrase("join my server")
+///         .min_reply_delay(Duration::from_secs(3));
+///     session.update_spam_classifier(Some(classifier));
+///     session.update_auto_skip(true);
+///     let events = session.get_events().await.unwrap();
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct SpamClassifier {
+    phrases: Vec<String>,
+    min_reply_delay: Duration,
+    threshold: f32,
+    link: Regex,
+}
+
+impl Default for SpamClassifier {
+    fn default() -> Self {
+        Self {
+            phrases: DEFAULT_PHRASES
+                .iter()
+                .map(|phrase| phrase.to_string())
+                .collect(),
+            min_reply_delay: Duration::from_secs(2),
+            threshold: 0.6,
+            link: Regex::new(LINK_PATTERN).expect("link pattern is valid"),
+        }
+    }
+}
+
+impl SpamClassifier {
+    /// Creates a new [`SpamClassifier`] with a few common spam phrases, a minimum reply
+    /// delay of 2 seconds and a threshold of 0.6
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a spam phrase, phrases are matched ignoring case
+    pub fn phrase<P: ToString>(mut self, phrase: P) -> Self {
+        self.phrases.push(phrase.to_string().to_lowercase());
+        self
+    }
+
+    /// Removes every spam phrase, including the default ones
+    pub fn clear_phrases(mut self) -> Self {
+        self.phrases.clear();
+        self
+    }
+
+    /// Sets how soon after connecting a first message is considered too fast for a human
+    pub fn min_reply_delay(mut self, min_reply_delay: Duration) -> Self {
+        self.min_reply_delay = min_reply_delay;
+        self
+    }
+
+    /// Sets the score from which a stranger is considered spam
+    pub fn threshold(mut self, threshold: f32) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// Scores a timeline
+    pub fn score(&self, timeline: &Timeline) -> SpamScore {
+        let mut signals = Vec::new();
+
+        if let Some((connected_at, first_message_at)) =
+            timeline.connected_at.zip(timeline.first_message_at)
+        {
+            let delay = first_message_at.saturating_duration_since(connected_at);
+            if delay < self.min_reply_delay {
+                signals.push(SpamSignal::FastFirstMessage(delay));
+            }
+        }
+        if !timeline.messages.is_empty() && !timeline.typed {
+            signals.push(SpamSignal::NeverTyped);
+        }
+
+        let lowercase_messages = timeline
+            .messages
+            .iter()
+            .map(|message| message.to_lowercase())
+            .collect::<Vec<_>>();
+        if let Some(phrase) = self.phrases.iter().find(|phrase| {
+            lowercase_messages
+                .iter()
+                .any(|message| message.contains(phrase.as_str()))
+        }) {
+            signals.push(SpamSignal::SpamPhrase(phrase.clone()));
+        }
+        if timeline
+            .messages
+            .iter()
+            .any(|message| self.link.is_match(message))
+        {
+            signals.push(SpamSignal::Link);
+        }
+
+        let score = signals.iter().map(SpamSignal::weight).sum::<f32>().min(1.0);
+        SpamScore {
+            score,
+            is_spam: score >= self.threshold,
+            signals,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flags_instant_link_pasting() {
+        let start = Instant::now();
+        let mut timeline = Timeline::new();
+        timeline.record_at(&ChatEvent::Connected, start);
+        timeline.end_batch();
+        timeline.record_at(
+            &ChatEvent::Message(String::from("Check out my pics at www.example.com")),
+            start + Duration::from_millis(300),
+        );
+
+        let score = SpamClassifier::new().score(&timeline);
+
+        assert_eq!(
+            score.signals,
+            vec![
+                SpamSignal::FastFirstMessage(Duration::from_millis(300)),
+                SpamSignal::NeverTyped,
+                SpamSignal::SpamPhrase(String::from("check out my")),
+                SpamSignal::Link,
+            ]
+        );
+        assert_eq!(score.score, 1.0);
+        assert!(score.is_spam)
+    }
+
+    #[test]
+    fn does_not_flag_humans() {
+        let start = Instant::now();
+        let mut timeline = Timeline::new();
+        timeline.record_at(&ChatEvent::Connected, start);
+        timeline.end_batch();
+        timeline.record_at(&ChatEvent::StartedTyping, start + Duration::from_secs(3));
+        timeline.record_at(
+            &ChatEvent::Message(String::from("hi, asl?")),
+            start + Duration::from_secs(5),
+        );
+
+        let score = SpamClassifier::new().score(&timeline);
+
+        assert!(score.signals.is_empty());
+        assert!(!score.is_spam)
+    }
+
+    #[test]
+    fn first_message_polled_with_the_connection_is_not_fast() {
+        let start = Instant::now();
+        let mut timeline = Timeline::new();
+        timeline.record_at(&ChatEvent::Connected, start);
+        timeline.record_at(&ChatEvent::Message(String::from("hi")), start);
+        timeline.end_batch();
+        timeline.record_at(
+            &ChatEvent::Message(String::from("asl?")),
+            start + Duration::from_millis(300),
+        );
+
+        assert_eq!(
+            SpamClassifier::new().score(&timeline).signals,
+            vec![SpamSignal::NeverTyped]
+        )
+    }
+
+    #[test]
+    fn connecting_starts_a_new_timeline() {
+        let start = Instant::now();
+        let mut timeline = Timeline::new();
+        timeline.record_at(&ChatEvent::Message(String::from("telegram")), start);
+        timeline.record_at(&ChatEvent::Connected, start);
+
+        assert!(timeline.get_messages().is_empty());
+        assert!(SpamClassifier::new().score(&timeline).signals.is_empty())
+    }
+
+    #[test]
+    fn uses_custom_phrases_and_threshold() {
+        let mut timeline = Timeline::new();
+        timeline.record(&ChatEvent::StartedTyping);
+        timeline.record(&ChatEvent::Message(String::from("Join my SERVER")));
+
+        let classifier = SpamClassifier::new()
+            .clear_phrases()
+            .phrase("join my server")
+            .threshold(0.4);
+
+        assert!(classifier.score(&timeline).is_spam);
+        assert!(!SpamClassifier::new().score(&timeline).is_spam)
+    }
+}
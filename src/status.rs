@@ -1,20 +1,47 @@
+use std::time::Duration;
+
+use crate::transport::{get_json, ReqwestTransport, Transport};
 use crate::types::chat_server::ChatServer;
 use crate::types::check_server::CheckServer;
+use crate::types::endpoints::Endpoints;
 use crate::types::error::OmegleLibError;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use vec1::Vec1;
 
+pub(crate) static OMEGLE_STATUS_URL: &str = "https://omegle.com/status";
+
+/// Turns a queue time in seconds into a [`Duration`], if it is a valid one
+fn queue_time(seconds: f64) -> Option<Duration> {
+    Duration::try_from_secs_f64(seconds).ok()
+}
+
 /// Type describing the status of the Omegle servers
 ///
 /// Used for building [`Omegle`](crate::omegle::Omegle) since it contains the info
 /// needed to initiate a new chat
 ///
-/// Can be aquired and used when necessary
-#[derive(Deserialize, Debug, PartialEq)]
+/// Can be acquired and used when necessary
+///
+/// Serializes to the same object omegle sends, leaving out the fields that are not set
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
 pub struct OmegleStatus {
     pub(crate) count: u64,
     pub(crate) servers: Vec1<ChatServer>,
     pub(crate) antinudeservers: Vec1<CheckServer>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) rtmfp: Option<String>,
+    #[serde(
+        rename = "spyQueueTime",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub(crate) spy_queue_time: Option<f64>,
+    #[serde(
+        rename = "spyeeQueueTime",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub(crate) spyee_queue_time: Option<f64>,
 }
 
 impl OmegleStatus {
@@ -23,6 +50,31 @@ impl OmegleStatus {
         self.count
     }
 
+    /// Get's the address of the peer-to-peer server advertised for video chats, if any
+    pub fn get_rtmfp(&self) -> Option<&str> {
+        self.rtmfp.as_deref()
+    }
+
+    /// Get's every chat server omegle advertises
+    pub fn get_chat_servers(&self) -> &[ChatServer] {
+        &self.servers
+    }
+
+    /// Get's every verification server omegle advertises
+    pub fn get_check_servers(&self) -> &[CheckServer] {
+        &self.antinudeservers
+    }
+
+    /// Get's how long spies currently wait for two strangers to discuss their question
+    pub fn get_spy_queue_time(&self) -> Option<Duration> {
+        self.spy_queue_time.and_then(queue_time)
+    }
+
+    /// Get's how long strangers currently wait for a spy's question
+    pub fn get_spyee_queue_time(&self) -> Option<Duration> {
+        self.spyee_queue_time.and_then(queue_time)
+    }
+
     /// Get's a chat server from the status data
     pub(crate) fn get_chat_server(&self) -> ChatServer {
         *self.servers.first()
@@ -51,8 +103,58 @@ impl OmegleStatus {
     /// - The response contained no text
     /// - The response was unexpected (Ex: Error on omegle's end or response was malformed)
     pub async fn get_omegle_status() -> Result<OmegleStatus, OmegleLibError> {
-        let req = reqwest::get("https://omegle.com/status").await?;
-        let omegle_status = req.json::<OmegleStatus>().await?;
+        Self::get_omegle_status_with(&ReqwestTransport::default()).await
+    }
+
+    /// Same as [`OmegleStatus::get_omegle_status`] but sends the request through `transport`
+    ///
+    /// # Errors
+    /// This function fails if:
+    /// - The omegle server cannot be reached
+    /// - The response contained no text
+    /// - The response was unexpected (Ex: Error on omegle's end or response was malformed)
+    pub async fn get_omegle_status_with(
+        transport: &dyn Transport,
+    ) -> Result<OmegleStatus, OmegleLibError> {
+        get_json::<OmegleStatus>(transport, OMEGLE_STATUS_URL).await
+    }
+
+    /// Same as [`OmegleStatus::get_omegle_status_with`] but fetches the status from the
+    /// status url of `endpoints`
+    ///
+    /// # Errors
+    /// This function fails if:
+    /// - The status url cannot be reached
+    /// - The response contained no text
+    /// - The response was unexpected (Ex: Error on omegle's end or response was malformed)
+    pub async fn get_omegle_status_from(
+        transport: &dyn Transport,
+        endpoints: &Endpoints,
+    ) -> Result<OmegleStatus, OmegleLibError> {
+        get_json::<OmegleStatus>(transport, endpoints.get_status_url()).await
+    }
+
+    /// Blocking version of [`OmegleStatus::get_omegle_status`]
+    ///
+    /// # Example:
+    ///
+    /// ```rust
+    /// use omegle_rs::status::OmegleStatus;
+    /// fn run() {
+    ///     let server_status = OmegleStatus::get_omegle_status_blocking().unwrap();
+    ///     println!("There are {} users currently active", server_status.get_count())
+    /// }
+    ///```
+    ///
+    /// # Errors
+    /// This function fails if:
+    /// - The omegle server cannot be reached
+    /// - The response contained no text
+    /// - The response was unexpected (Ex: Error on omegle's end or response was malformed)
+    #[cfg(feature = "blocking")]
+    pub fn get_omegle_status_blocking() -> Result<OmegleStatus, OmegleLibError> {
+        let req = reqwest::blocking::get(OMEGLE_STATUS_URL)?;
+        let omegle_status = req.json::<OmegleStatus>()?;
         Ok(omegle_status)
     }
 }
@@ -61,7 +163,7 @@ impl OmegleStatus {
 mod tests {
     use super::*;
 
-    use serde_test::{assert_de_tokens, assert_de_tokens_error, Token};
+    use serde_test::{assert_de_tokens, assert_de_tokens_error, assert_tokens, Token};
 
     use vec1::vec1;
 
@@ -76,6 +178,9 @@ mod tests {
                 CheckServer(1),
                 CheckServer(3)
             ],
+            rtmfp: Some(String::from("rtmfp://p2p.rtmfp.net")),
+            spy_queue_time: Some(105.28910002708434),
+            spyee_queue_time: Some(229.33259999752045),
         };
         assert_de_tokens(
             &expected_val,
@@ -91,12 +196,15 @@ mod tests {
                 Token::BorrowedStr("waw3.omegle.com"),
                 Token::SeqEnd,
                 Token::BorrowedStr("spyQueueTime"),
+                Token::Some,
                 Token::F64(105.28910002708434),
                 Token::BorrowedStr("rtmfp"),
+                Token::Some,
                 Token::BorrowedStr("rtmfp://p2p.rtmfp.net"),
                 Token::BorrowedStr("antinudepercent"),
                 Token::F32(1.0),
                 Token::BorrowedStr("spyeeQueueTime"),
+                Token::Some,
                 Token::F64(229.33259999752045),
                 Token::BorrowedStr("timestamp"),
                 Token::F64(1685331229.225212),
@@ -125,12 +233,15 @@ mod tests {
                 Token::BorrowedStr("waw3.omegle.com"),
                 Token::SeqEnd,
                 Token::BorrowedStr("spyQueueTime"),
+                Token::Some,
                 Token::F64(105.28910002708434),
                 Token::BorrowedStr("rtmfp"),
+                Token::Some,
                 Token::BorrowedStr("rtmfp://p2p.rtmfp.net"),
                 Token::BorrowedStr("antinudepercent"),
                 Token::F32(1.0),
                 Token::BorrowedStr("spyeeQueueTime"),
+                Token::Some,
                 Token::F64(229.33259999752045),
                 Token::BorrowedStr("timestamp"),
                 Token::F64(1685331229.225212),
@@ -142,6 +253,58 @@ mod tests {
         )
     }
 
+    #[test]
+    fn round_trips_leaving_out_unset_fields() {
+        let status = OmegleStatus {
+            count: 12,
+            servers: vec1![ChatServer { id_number: 3 }],
+            antinudeservers: vec1![CheckServer(2)],
+            rtmfp: None,
+            spy_queue_time: Some(1.5),
+            spyee_queue_time: None,
+        };
+        assert_tokens(
+            &status,
+            &[
+                Token::Struct {
+                    name: "OmegleStatus",
+                    len: 4,
+                },
+                Token::Str("count"),
+                Token::U64(12),
+                Token::Str("servers"),
+                Token::Seq { len: Some(1) },
+                Token::Str("front3"),
+                Token::SeqEnd,
+                Token::Str("antinudeservers"),
+                Token::Seq { len: Some(1) },
+                Token::Str("waw2.omegle.com"),
+                Token::SeqEnd,
+                Token::Str("spyQueueTime"),
+                Token::Some,
+                Token::F64(1.5),
+                Token::StructEnd,
+            ],
+        )
+    }
+
+    #[test]
+    fn queue_times_are_durations() {
+        let status = OmegleStatus {
+            count: 1,
+            servers: vec1![ChatServer { id_number: 1 }],
+            antinudeservers: vec1![CheckServer(1)],
+            rtmfp: None,
+            spy_queue_time: Some(1.5),
+            spyee_queue_time: Some(-1.0),
+        };
+        assert_eq!(
+            status.get_spy_queue_time(),
+            Some(Duration::from_millis(1500))
+        );
+        assert_eq!(status.get_spyee_queue_time(), None)
+    }
+
     #[test]
     fn invalid_response_text_should_error() {
         assert_de_tokens_error::<OmegleStatus>(
@@ -0,0 +1,44 @@
+use async_trait::async_trait;
+use reqwest::Client;
+
+use crate::transport::Transport;
+use crate::types::error::OmegleLibError;
+
+/// Default [`Transport`] that sends requests with a [`reqwest::Client`]
+#[derive(Debug, Clone, Default)]
+pub struct ReqwestTransport {
+    client: Client,
+}
+
+impl ReqwestTransport {
+    /// Creates a new [`ReqwestTransport`] from an already configured [`Client`]
+    pub fn new(client: Client) -> Self {
+        Self { client }
+    }
+}
+
+impl From<Client> for ReqwestTransport {
+    fn from(client: Client) -> Self {
+        Self::new(client)
+    }
+}
+
+#[async_trait]
+impl Transport for ReqwestTransport {
+    async fn post_form(&self, url: &str, form: &[(&str, &str)]) -> Result<String, OmegleLibError> {
+        let resp = self
+            .client
+            .post(url)
+            .form(form)
+            .send()
+            .await?
+            .text()
+            .await?;
+        Ok(resp)
+    }
+
+    async fn get(&self, url: &str) -> Result<String, OmegleLibError> {
+        let resp = self.client.get(url).send().await?.text().await?;
+        Ok(resp)
+    }
+}
@@ -0,0 +1,198 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use reqwest::Url;
+
+use crate::transport::Transport;
+use crate::types::error::OmegleLibError;
+
+/// The kind of request that was made to a [`MemoryTransport`]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum RequestMethod {
+    /// Made with [`Transport::get`]
+    Get,
+    /// Made with [`Transport::post_form`]
+    Post,
+}
+
+/// A request that was made to a [`MemoryTransport`]
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct RecordedRequest {
+    /// How the request was made
+    pub method: RequestMethod,
+    /// The full url, query included
+    pub url: String,
+    /// The posted form fields in order, empty for GET requests
+    pub form: Vec<(String, String)>,
+}
+
+#[derive(Debug)]
+enum ScriptedResponse {
+    Body(String),
+    Stall,
+}
+
+/// In-memory [`Transport`] that answers requests with scripted responses
+///
+/// Responses are queued per url path (Ex: `/send`, `/events`) and handed out
+/// in the order they were pushed. Every request is recorded so it can be
+/// inspected afterwards. A stall can be queued instead of a response to simulate
+/// a server that never answers.
+///
+/// # Examples
+/// ```rust
+/// use omegle_rs::transport::{MemoryTransport, Transport};
+///
+/// async fn run() {
+///     let transport = MemoryTransport::new();
+///     transport.push_response("/send", "win");
+///     let resp = transport
+///         .post_form("http://front1.omegle.com/send", &[("msg", "hi")])
+///         .await
+///         .unwrap();
+///     assert_eq!(resp, "win");
+///     assert_eq!(transport.requests().len(), 1);
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct MemoryTransport {
+    responses: Mutex<HashMap<String, VecDeque<ScriptedResponse>>>,
+    requests: Mutex<Vec<RecordedRequest>>,
+}
+
+impl MemoryTransport {
+    /// Creates a new [`MemoryTransport`] with no scripted responses
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queues a response for the next request made to `path`
+    pub fn push_response<P: ToString, B: ToString>(&self, path: P, body: B) {
+        self.responses
+            .lock()
+            .expect("lock is not poisoned")
+            .entry(path.to_string())
+            .or_default()
+            .push_back(ScriptedResponse::Body(body.to_string()));
+    }
+
+    /// Makes the next request made to `path` never complete
+    pub fn push_stall<P: ToString>(&self, path: P) {
+        self.responses
+            .lock()
+            .expect("lock is not poisoned")
+            .entry(path.to_string())
+            .or_default()
+            .push_back(ScriptedResponse::Stall);
+    }
+
+    /// Gets all the requests made so far, oldest first
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.requests.lock().expect("lock is not poisoned").clone()
+    }
+
+    async fn respond(
+        &self,
+        method: RequestMethod,
+        url: &str,
+        form: &[(&str, &str)],
+    ) -> Result<String, OmegleLibError> {
+        self.requests
+            .lock()
+            .expect("lock is not poisoned")
+            .push(RecordedRequest {
+                method,
+                url: url.to_string(),
+                form: form
+                    .iter()
+                    .map(|(key, value)| (key.to_string(), value.to_string()))
+                    .collect(),
+            });
+
+        let parsed_url = Url::parse(url)
+            .map_err(|err| OmegleLibError::TransportError(format!("invalid url '{url}': {err}")))?;
+        let path = parsed_url.path();
+        let response = self
+            .responses
+            .lock()
+            .expect("lock is not poisoned")
+            .get_mut(path)
+            .and_then(VecDeque::pop_front);
+        match response {
+            Some(ScriptedResponse::Body(body)) => Ok(body),
+            Some(ScriptedResponse::Stall) => futures::future::pending().await,
+            None => Err(OmegleLibError::TransportError(format!(
+                "no scripted response for '{path}'"
+            ))),
+        }
+    }
+}
+
+#[async_trait]
+impl Transport for MemoryTransport {
+    async fn post_form(&self, url: &str, form: &[(&str, &str)]) -> Result<String, OmegleLibError> {
+        self.respond(RequestMethod::Post, url, form).await
+    }
+
+    async fn get(&self, url: &str) -> Result<String, OmegleLibError> {
+        self.respond(RequestMethod::Get, url, &[]).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::executor::block_on;
+    use futures::FutureExt;
+
+    use super::*;
+
+    #[test]
+    fn responds_in_order_per_path() {
+        let transport = MemoryTransport::new();
+        transport.push_response("/send", "win");
+        transport.push_response("/send", "fail");
+        transport.push_response("/typing", "win");
+
+        let url = "http://front1.omegle.com/send";
+        assert_eq!(block_on(transport.post_form(url, &[])).unwrap(), "win");
+        assert_eq!(block_on(transport.post_form(url, &[])).unwrap(), "fail");
+        assert_eq!(
+            block_on(transport.post_form("http://front1.omegle.com/typing", &[])).unwrap(),
+            "win"
+        );
+    }
+
+    #[test]
+    fn errors_without_scripted_response() {
+        let transport = MemoryTransport::new();
+        let resp = block_on(transport.get("https://omegle.com/status"));
+        assert!(matches!(resp, Err(OmegleLibError::TransportError(_))))
+    }
+
+    #[test]
+    fn stalls_when_scripted() {
+        let transport = MemoryTransport::new();
+        transport.push_stall("/events");
+        let resp = transport
+            .post_form("http://front1.omegle.com/events", &[])
+            .now_or_never();
+        assert!(resp.is_none())
+    }
+
+    #[test]
+    fn records_requests() {
+        let transport = MemoryTransport::new();
+        transport.push_response("/send", "win");
+        block_on(transport.post_form("http://front1.omegle.com/send", &[("msg", "hi")])).unwrap();
+
+        assert_eq!(
+            transport.requests(),
+            vec![RecordedRequest {
+                method: RequestMethod::Post,
+                url: String::from("http://front1.omegle.com/send"),
+                form: vec![(String::from("msg"), String::from("hi"))],
+            }]
+        )
+    }
+}
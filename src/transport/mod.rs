@@ -0,0 +1,65 @@
+//! Abstraction over the HTTP requests the library makes
+//!
+//! [`Omegle`](crate::omegle::Omegle), [`ChatSession`](crate::chat_session::ChatSession) and
+//! [`OmegleStatus`](crate::status::OmegleStatus) only ever post forms and fetch text, so
+//! anything implementing [`Transport`] can be plugged in. [`ReqwestTransport`] is used by
+//! default and [`MemoryTransport`] can be used to script responses in tests. With the
+//! `proxy` feature, a `ProxyPool` spreads chats over HTTP and SOCKS5 proxies.
+
+use std::fmt::Debug;
+
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+
+use crate::types::error::OmegleLibError;
+
+mod http;
+mod memory;
+#[cfg(feature = "proxy")]
+mod proxy;
+
+pub use http::ReqwestTransport;
+pub use memory::{MemoryTransport, RecordedRequest, RequestMethod};
+#[cfg(feature = "proxy")]
+pub use proxy::{PinnedTransport, ProxyPool};
+
+/// Trait describing how requests are sent to omegle
+#[async_trait]
+pub trait Transport: Debug + Send + Sync {
+    /// Sends a POST request with `form` as the url encoded body and returns the response text
+    ///
+    /// # Errors
+    /// Fails if the request could not be completed
+    async fn post_form(&self, url: &str, form: &[(&str, &str)]) -> Result<String, OmegleLibError>;
+
+    /// Sends a GET request and returns the response text
+    ///
+    /// # Errors
+    /// Fails if the request could not be completed
+    async fn get(&self, url: &str) -> Result<String, OmegleLibError>;
+
+    /// Called when omegle banned the chat the requests were sent for
+    ///
+    /// Does nothing by default, transports that can change how they appear to omegle
+    /// use it to stop sending requests the banned way
+    fn report_ban(&self) {}
+}
+
+/// Posts a form and deserializes the JSON response
+pub(crate) async fn post_form_json<T: DeserializeOwned>(
+    transport: &dyn Transport,
+    url: &str,
+    form: &[(&str, &str)],
+) -> Result<T, OmegleLibError> {
+    let resp = transport.post_form(url, form).await?;
+    Ok(serde_json::from_str(&resp)?)
+}
+
+/// Sends a GET request and deserializes the JSON response
+pub(crate) async fn get_json<T: DeserializeOwned>(
+    transport: &dyn Transport,
+    url: &str,
+) -> Result<T, OmegleLibError> {
+    let resp = transport.get(url).await?;
+    Ok(serde_json::from_str(&resp)?)
+}
@@ -0,0 +1,324 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use reqwest::{Client, Proxy};
+
+use crate::transport::{ReqwestTransport, Transport};
+use crate::types::error::OmegleLibError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Health {
+    Healthy,
+    Unreachable,
+    Banned,
+}
+
+#[derive(Debug)]
+struct PooledProxy {
+    url: String,
+    transport: Arc<dyn Transport>,
+    health: Mutex<Health>,
+}
+
+impl PooledProxy {
+    fn get_health(&self) -> Health {
+        *self.health.lock().expect("lock is not poisoned")
+    }
+
+    /// Sets the health of the proxy, returning the previous one
+    fn update_health(&self, health: Health) -> Health {
+        std::mem::replace(
+            &mut self.health.lock().expect("lock is not poisoned"),
+            health,
+        )
+    }
+}
+
+/// Pool of proxies new chats are spread over, requires the `proxy` feature
+///
+/// Every chat is pinned to one proxy picked in turn, so all of its requests come
+/// from the same address. A proxy is removed from the pool once one of its chats
+/// gets banned or a request through it fails to connect, and can be put back with
+/// [`ProxyPool::restore`]. An [`Omegle`](crate::omegle::Omegle) using the pool puts
+/// banned proxies back once its [`BanPolicy`](crate::ban::BanPolicy) cooldown is over.
+///
+/// # Examples
+/// Start chats through two proxies
+/// ```rust
+/// use omegle_rs::omegle::Omegle;
+/// use omegle_rs::transport::ProxyPool;
+/// use std::sync::Arc;
+///
+/// fn use_proxies(omegle: &mut Omegle) {
+///     let pool = ProxyPool::new(["http://10.0.0.1:8080", "socks5://10.0.0.2:1080"]).unwrap();
+///     omegle.update_proxy_pool(Some(Arc::new(pool)))
+/// }
+/// ```
+#[derive(Debug)]
+pub struct ProxyPool {
+    proxies: Vec<PooledProxy>,
+    next: AtomicUsize,
+}
+
+impl ProxyPool {
+    /// Creates a new [`ProxyPool`] from proxy urls (Ex: `http://host:port`, `socks5://host:port`)
+    ///
+    /// # Errors
+    /// This function fails if a url is not a valid proxy
+    pub fn new<I, U>(urls: I) -> Result<Self, OmegleLibError>
+    where
+        I: IntoIterator<Item = U>,
+        U: ToString,
+    {
+        let proxies = urls
+            .into_iter()
+            .map(|url| {
+                let url = url.to_string();
+                let client = Client::builder().proxy(Proxy::all(&url)?).build()?;
+                Ok((
+                    url,
+                    Arc::new(ReqwestTransport::new(client)) as Arc<dyn Transport>,
+                ))
+            })
+            .collect::<Result<Vec<_>, OmegleLibError>>()?;
+        Ok(Self::with_transports(proxies))
+    }
+
+    /// Creates a new [`ProxyPool`] where requests through each proxy are sent with its own transport
+    pub fn with_transports<I>(proxies: I) -> Self
+    where
+        I: IntoIterator<Item = (String, Arc<dyn Transport>)>,
+    {
+        Self {
+            proxies: proxies
+                .into_iter()
+                .map(|(url, transport)| PooledProxy {
+                    url,
+                    transport,
+                    health: Mutex::new(Health::Healthy),
+                })
+                .collect(),
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    /// Picks the next healthy proxy for a new chat
+    ///
+    /// # Errors
+    /// This function fails with [`OmegleLibError::NoHealthyProxy`] if every proxy was removed
+    pub fn pin(self: &Arc<Self>) -> Result<PinnedTransport, OmegleLibError> {
+        let start = self.next.fetch_add(1, Ordering::Relaxed);
+        (0..self.proxies.len())
+            .map(|offset| (start + offset) % self.proxies.len())
+            .find(|index| self.proxies[*index].get_health() == Health::Healthy)
+            .map(|index| PinnedTransport {
+                pool: self.clone(),
+                index,
+            })
+            .ok_or(OmegleLibError::NoHealthyProxy)
+    }
+
+    /// Puts a removed proxy back in the pool, returns whether it was removed
+    pub fn restore(&self, url: &str) -> bool {
+        self.proxies
+            .iter()
+            .filter(|proxy| proxy.url == url)
+            .any(|proxy| proxy.update_health(Health::Healthy) != Health::Healthy)
+    }
+
+    /// Puts every proxy that was removed because of a ban back in the pool
+    pub fn restore_banned(&self) {
+        for proxy in &self.proxies {
+            if proxy.get_health() == Health::Banned {
+                proxy.update_health(Health::Healthy);
+            }
+        }
+    }
+
+    /// Gets the urls of the proxies still in the pool
+    pub fn get_healthy(&self) -> Vec<&str> {
+        self.proxies
+            .iter()
+            .filter(|proxy| proxy.get_health() == Health::Healthy)
+            .map(|proxy| proxy.url.as_str())
+            .collect()
+    }
+
+    /// Gets the urls of the proxies that were removed
+    pub fn get_removed(&self) -> Vec<&str> {
+        self.proxies
+            .iter()
+            .filter(|proxy| proxy.get_health() != Health::Healthy)
+            .map(|proxy| proxy.url.as_str())
+            .collect()
+    }
+
+    fn remove(&self, index: usize, health: Health) {
+        self.proxies[index].update_health(health);
+    }
+}
+
+/// [`Transport`] sending every request through the proxy a chat was pinned to
+///
+/// Created by [`ProxyPool::pin`], reports bans and connection errors back to the pool
+#[derive(Debug, Clone)]
+pub struct PinnedTransport {
+    pool: Arc<ProxyPool>,
+    index: usize,
+}
+
+impl PinnedTransport {
+    /// Gets the url of the proxy requests are sent through
+    pub fn get_proxy_url(&self) -> &str {
+        &self.pool.proxies[self.index].url
+    }
+
+    fn transport(&self) -> &dyn Transport {
+        self.pool.proxies[self.index].transport.as_ref()
+    }
+
+    /// Removes the proxy from the pool if `result` shows it could not be reached
+    fn check(&self, result: Result<String, OmegleLibError>) -> Result<String, OmegleLibError> {
+        let unreachable = match &result {
+            Err(OmegleLibError::ReqwestError(error)) => error.is_connect() || error.is_timeout(),
+            Err(OmegleLibError::TransportError(_)) => true,
+            _ => false,
+        };
+        if unreachable {
+            self.pool.remove(self.index, Health::Unreachable);
+        }
+        result
+    }
+}
+
+#[async_trait]
+impl Transport for PinnedTransport {
+    async fn post_form(&self, url: &str, form: &[(&str, &str)]) -> Result<String, OmegleLibError> {
+        self.check(self.transport().post_form(url, form).await)
+    }
+
+    async fn get(&self, url: &str) -> Result<String, OmegleLibError> {
+        self.check(self.transport().get(url).await)
+    }
+
+    fn report_ban(&self) {
+        self.pool.remove(self.index, Health::Banned)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use futures::executor::block_on;
+
+    use crate::omegle::{tests::test_status, Omegle};
+    use crate::transport::MemoryTransport;
+    use crate::types::chat_event::ChatEvent;
+    use crate::types::lang::LangCode;
+
+    use super::*;
+
+    fn memory_pool(count: usize) -> (Arc<ProxyPool>, Vec<Arc<MemoryTransport>>) {
+        let transports = (0..count)
+            .map(|_| Arc::new(MemoryTransport::new()))
+            .collect::<Vec<_>>();
+        let pool =
+            ProxyPool::with_transports(transports.iter().enumerate().map(|(index, transport)| {
+                (
+                    format!("http://proxy{index}"),
+                    transport.clone() as Arc<dyn Transport>,
+                )
+            }));
+        (Arc::new(pool), transports)
+    }
+
+    #[test]
+    fn pins_proxies_in_turn() {
+        let (pool, _) = memory_pool(2);
+        let pinned = (0..3)
+            .map(|_| pool.pin().unwrap().get_proxy_url().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            pinned,
+            vec!["http://proxy0", "http://proxy1", "http://proxy0"]
+        )
+    }
+
+    #[test]
+    fn removes_unreachable_proxies() {
+        let (pool, transports) = memory_pool(2);
+        transports[1].push_response("/send", "win");
+        let first = pool.pin().unwrap();
+        let second = pool.pin().unwrap();
+
+        assert!(block_on(first.post_form("http://front1.omegle.com/send", &[])).is_err());
+        assert_eq!(
+            block_on(second.post_form("http://front1.omegle.com/send", &[])).unwrap(),
+            "win"
+        );
+
+        assert_eq!(pool.get_removed(), vec!["http://proxy0"]);
+        assert_eq!(pool.pin().unwrap().get_proxy_url(), "http://proxy1");
+        assert!(pool.restore("http://proxy0"));
+        assert!(!pool.restore("http://proxy0"));
+        assert_eq!(pool.get_healthy().len(), 2)
+    }
+
+    #[test]
+    fn fails_once_every_proxy_is_removed() {
+        let (pool, _) = memory_pool(2);
+        pool.pin().unwrap().report_ban();
+        assert!(block_on(pool.pin().unwrap().get("http://omegle.com/status")).is_err());
+        assert!(matches!(pool.pin(), Err(OmegleLibError::NoHealthyProxy)));
+
+        pool.restore_banned();
+        assert_eq!(pool.get_healthy(), vec!["http://proxy0"]);
+        assert!(matches!(
+            Arc::new(ProxyPool::with_transports([])).pin(),
+            Err(OmegleLibError::NoHealthyProxy)
+        ))
+    }
+
+    #[test]
+    fn rejects_invalid_proxy_urls() {
+        assert!(ProxyPool::new(["http://10.0.0.1:8080", "socks5://10.0.0.2:1080"]).is_ok());
+        assert!(ProxyPool::new(["not a proxy"]).is_err())
+    }
+
+    #[test]
+    fn chats_are_pinned_and_bans_remove_the_proxy() {
+        let (pool, transports) = memory_pool(2);
+        for transport in &transports {
+            transport.push_response("/check", "checkcode");
+            transport.push_response(
+                "/start",
+                r#"{"clientID": "central1:aaaaaaaaaaaaaaaaaaaaaaaaaaaaaa", "events": [["waiting"]]}"#,
+            );
+        }
+        transports[0].push_response("/events", r#"[["antinudeBanned"]]"#);
+        let mut omegle = Omegle::with_transport(
+            test_status(),
+            HashSet::new(),
+            LangCode::English,
+            Arc::new(MemoryTransport::new()),
+        );
+        omegle.update_proxy_pool(Some(pool.clone()));
+
+        let first = block_on(omegle.new_chat()).unwrap();
+        let _second = block_on(omegle.new_chat()).unwrap();
+        assert_eq!(transports[1].requests().len(), 2);
+
+        assert_eq!(
+            block_on(first.get_events()).unwrap().into_vec(),
+            vec![ChatEvent::Waiting]
+        );
+        assert_eq!(
+            block_on(first.get_events()).unwrap().into_vec(),
+            vec![ChatEvent::Banned]
+        );
+        assert_eq!(transports[0].requests().len(), 3);
+        assert_eq!(pool.get_healthy(), vec!["http://proxy1"])
+    }
+}
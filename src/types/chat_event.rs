@@ -2,13 +2,21 @@ use std::fmt;
 
 use serde::{
     de::{Error, SeqAccess, Visitor},
-    Deserialize, Deserializer,
+    ser::SerializeSeq,
+    Deserialize, Deserializer, Serialize, Serializer,
 };
 use vec1::Vec1;
 
 use crate::status::OmegleStatus;
+use crate::types::filter_match::{FilterAction, FilterMatch};
+use crate::types::rtc::{IceCandidate, RtcSessionDescription};
+use crate::types::spam_score::SpamScore;
 
 /// Enum representing every possible chat event you can receive from the server
+///
+/// Serializes to the list omegle sends (Ex: `["gotMessage", "hi"]`). Local events
+/// serialize with identifiers omegle never sends (Ex: `["idleTimeout", true]`) and
+/// deserialize back, so any event can be stored or forwarded
 #[derive(Debug, PartialEq)]
 pub enum ChatEvent {
     // Status Events
@@ -20,6 +28,8 @@ pub enum ChatEvent {
     // Notifications
     CommonLikes(Vec1<String>),
     ServerMessage(String),
+    /// The stranger's college, only sent in [`ChatMode::College`](crate::types::chat_mode::ChatMode::College)
+    PartnerCollege(String),
     // Not sure what this is even used for but gotta include it so it doesn't error
     IdentDigests(String),
 
@@ -33,6 +43,169 @@ pub enum ChatEvent {
     StoppedTyping,
     Message(String),
     Disconnected,
+
+    // Spy mode, strangers are named by omegle (Ex: `Stranger 1`)
+    /// The question being discussed, only sent in [`ChatMode::Spy`](crate::types::chat_mode::ChatMode::Spy)
+    /// and [`ChatMode::Spyee`](crate::types::chat_mode::ChatMode::Spyee)
+    Question(String),
+    /// One of the watched strangers started typing
+    SpyTyping(String),
+    /// One of the watched strangers stopped typing
+    SpyStoppedTyping(String),
+    /// One of the watched strangers sent a message
+    SpyMessage {
+        stranger: String,
+        message: String,
+    },
+    /// One of the watched strangers left, which ends the chat
+    SpyDisconnected(String),
+
+    // Video chat signaling
+    /// Sent to the party that should create the WebRTC offer
+    RtcCall,
+    RtcPeerDescription(RtcSessionDescription),
+    IceCandidate(IceCandidate),
+
+    // Local events
+    /// Never sent by omegle, returned by [`ChatSession::get_events`](crate::chat_session::ChatSession::get_events)
+    /// when the stranger was idle for longer than the session's idle timeout
+    IdleTimeout {
+        disconnected: bool,
+    },
+    /// Never sent by omegle, reports a message that matched a rule of the session's
+    /// [filter](crate::chat_session::ChatSession::update_filter)
+    Filtered(FilterMatch),
+    /// Never sent by omegle, returned when the session's
+    /// [spam classifier](crate::chat_session::ChatSession::update_spam_classifier)
+    /// flagged the stranger
+    SpamDetected {
+        score: SpamScore,
+        disconnected: bool,
+    },
+}
+
+impl ChatEvent {
+    /// Whether no more events will follow this one in the current chat
+    pub fn ends_chat(&self) -> bool {
+        matches!(
+            self,
+            ChatEvent::Disconnected
+                | ChatEvent::SpyDisconnected(_)
+                | ChatEvent::ConnectionDied
+                | ChatEvent::Banned
+                | ChatEvent::Error(_)
+                | ChatEvent::IdleTimeout { disconnected: true }
+                | ChatEvent::SpamDetected {
+                    disconnected: true,
+                    ..
+                }
+                | ChatEvent::Filtered(FilterMatch {
+                    action: FilterAction::Disconnect,
+                    ..
+                })
+        )
+    }
+}
+
+/// Serializes an event as its identifier followed by its payload, if any
+fn serialize_event<S, P>(
+    serializer: S,
+    identifier: &str,
+    payload: Option<&P>,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    P: Serialize,
+{
+    let mut seq = serializer.serialize_seq(Some(1 + usize::from(payload.is_some())))?;
+    seq.serialize_element(identifier)?;
+    if let Some(payload) = payload {
+        seq.serialize_element(payload)?;
+    }
+    seq.end()
+}
+
+impl Serialize for ChatEvent {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            ChatEvent::Waiting => serialize_event::<_, ()>(serializer, "waiting", None),
+            ChatEvent::Connected => serialize_event::<_, ()>(serializer, "connected", None),
+            ChatEvent::StatusInfo(status) => {
+                serialize_event(serializer, "statusInfo", Some(status))
+            }
+            ChatEvent::Count(count) => serialize_event(serializer, "count", Some(count)),
+            ChatEvent::CommonLikes(likes) => {
+                serialize_event(serializer, "commonLikes", Some(likes))
+            }
+            ChatEvent::ServerMessage(message) => {
+                serialize_event(serializer, "serverMessage", Some(message))
+            }
+            ChatEvent::PartnerCollege(college) => {
+                serialize_event(serializer, "partnerCollege", Some(college))
+            }
+            ChatEvent::IdentDigests(digests) => {
+                serialize_event(serializer, "identDigests", Some(digests))
+            }
+            ChatEvent::Error(message) => serialize_event(serializer, "error", Some(message)),
+            ChatEvent::ConnectionDied => {
+                serialize_event::<_, ()>(serializer, "connectionDied", None)
+            }
+            ChatEvent::Banned => serialize_event::<_, ()>(serializer, "antinudeBanned", None),
+            ChatEvent::StartedTyping => serialize_event::<_, ()>(serializer, "typing", None),
+            ChatEvent::StoppedTyping => serialize_event::<_, ()>(serializer, "stoppedTyping", None),
+            ChatEvent::Message(message) => serialize_event(serializer, "gotMessage", Some(message)),
+            ChatEvent::Disconnected => {
+                serialize_event::<_, ()>(serializer, "strangerDisconnected", None)
+            }
+            ChatEvent::Question(question) => {
+                serialize_event(serializer, "question", Some(question))
+            }
+            ChatEvent::SpyTyping(stranger) => {
+                serialize_event(serializer, "spyTyping", Some(stranger))
+            }
+            ChatEvent::SpyStoppedTyping(stranger) => {
+                serialize_event(serializer, "spyStoppedTyping", Some(stranger))
+            }
+            ChatEvent::SpyMessage { stranger, message } => {
+                // Followed by both the stranger and the message
+                let mut seq = serializer.serialize_seq(Some(3))?;
+                seq.serialize_element("spyMessage")?;
+                seq.serialize_element(stranger)?;
+                seq.serialize_element(message)?;
+                seq.end()
+            }
+            ChatEvent::SpyDisconnected(stranger) => {
+                serialize_event(serializer, "spyDisconnected", Some(stranger))
+            }
+            ChatEvent::RtcCall => serialize_event::<_, ()>(serializer, "rtccall", None),
+            ChatEvent::RtcPeerDescription(description) => {
+                serialize_event(serializer, "rtcpeerdescription", Some(description))
+            }
+            ChatEvent::IceCandidate(candidate) => {
+                serialize_event(serializer, "icecandidate", Some(candidate))
+            }
+            ChatEvent::IdleTimeout { disconnected } => {
+                serialize_event(serializer, "idleTimeout", Some(disconnected))
+            }
+            ChatEvent::Filtered(filter_match) => {
+                serialize_event(serializer, "filtered", Some(filter_match))
+            }
+            ChatEvent::SpamDetected {
+                score,
+                disconnected,
+            } => {
+                // The only event with two payload elements
+                let mut seq = serializer.serialize_seq(Some(3))?;
+                seq.serialize_element("spamDetected")?;
+                seq.serialize_element(score)?;
+                seq.serialize_element(disconnected)?;
+                seq.end()
+            }
+        }
+    }
 }
 
 impl<'de> Deserialize<'de> for ChatEvent {
@@ -88,6 +261,12 @@ impl<'de> Visitor<'de> for ChatEventVisitor {
                 ))?;
                 ChatEvent::ServerMessage(server_msg)
             }
+            "partnerCollege" => {
+                let college = seq.next_element::<String>()?.ok_or(Error::custom(
+                    "expected partnerCollege to be followed by a string",
+                ))?;
+                ChatEvent::PartnerCollege(college)
+            }
             "identDigests" => {
                 let digests = seq.next_element::<String>()?.ok_or(Error::custom(
                     "expected identDigests to be followed by a string",
@@ -111,6 +290,78 @@ impl<'de> Visitor<'de> for ChatEventVisitor {
                 ChatEvent::Message(msg)
             }
             "strangerDisconnected" => ChatEvent::Disconnected,
+            "question" => {
+                let question = seq.next_element::<String>()?.ok_or(Error::custom(
+                    "expected question to be followed by a string",
+                ))?;
+                ChatEvent::Question(question)
+            }
+            "spyTyping" => {
+                let stranger = seq.next_element::<String>()?.ok_or(Error::custom(
+                    "expected spyTyping to be followed by a stranger",
+                ))?;
+                ChatEvent::SpyTyping(stranger)
+            }
+            "spyStoppedTyping" => {
+                let stranger = seq.next_element::<String>()?.ok_or(Error::custom(
+                    "expected spyStoppedTyping to be followed by a stranger",
+                ))?;
+                ChatEvent::SpyStoppedTyping(stranger)
+            }
+            "spyMessage" => {
+                let stranger = seq.next_element::<String>()?.ok_or(Error::custom(
+                    "expected spyMessage to be followed by a stranger",
+                ))?;
+                let message = seq.next_element::<String>()?.ok_or(Error::custom(
+                    "expected spyMessage to be followed by a stranger and a message",
+                ))?;
+                ChatEvent::SpyMessage { stranger, message }
+            }
+            "spyDisconnected" => {
+                let stranger = seq.next_element::<String>()?.ok_or(Error::custom(
+                    "expected spyDisconnected to be followed by a stranger",
+                ))?;
+                ChatEvent::SpyDisconnected(stranger)
+            }
+            "rtccall" => ChatEvent::RtcCall,
+            "rtcpeerdescription" => {
+                let desc = seq
+                    .next_element::<RtcSessionDescription>()?
+                    .ok_or(Error::custom(
+                        "expected rtcpeerdescription to be followed by a session description",
+                    ))?;
+                ChatEvent::RtcPeerDescription(desc)
+            }
+            "icecandidate" => {
+                let candidate = seq.next_element::<IceCandidate>()?.ok_or(Error::custom(
+                    "expected icecandidate to be followed by an ice candidate",
+                ))?;
+                ChatEvent::IceCandidate(candidate)
+            }
+            "idleTimeout" => {
+                let disconnected = seq.next_element::<bool>()?.ok_or(Error::custom(
+                    "expected idleTimeout to be followed by a bool",
+                ))?;
+                ChatEvent::IdleTimeout { disconnected }
+            }
+            "filtered" => {
+                let filter_match = seq.next_element::<FilterMatch>()?.ok_or(Error::custom(
+                    "expected filtered to be followed by a filter match",
+                ))?;
+                ChatEvent::Filtered(filter_match)
+            }
+            "spamDetected" => {
+                let score = seq.next_element::<SpamScore>()?.ok_or(Error::custom(
+                    "expected spamDetected to be followed by a spam score",
+                ))?;
+                let disconnected = seq.next_element::<bool>()?.ok_or(Error::custom(
+                    "expected spamDetected to be followed by a spam score and a bool",
+                ))?;
+                ChatEvent::SpamDetected {
+                    score,
+                    disconnected,
+                }
+            }
             _ => Err(Error::unknown_variant(
                 chat_event_identifier,
                 &[
@@ -120,6 +371,7 @@ impl<'de> Visitor<'de> for ChatEventVisitor {
                     "count",
                     "commonLikes",
                     "serverMessage",
+                    "partnerCollege",
                     "identDigests",
                     "error",
                     "connectionDied",
@@ -128,6 +380,17 @@ impl<'de> Visitor<'de> for ChatEventVisitor {
                     "stoppedTyping",
                     "gotMessage",
                     "strangerDisconnected",
+                    "question",
+                    "spyTyping",
+                    "spyStoppedTyping",
+                    "spyMessage",
+                    "spyDisconnected",
+                    "rtccall",
+                    "rtcpeerdescription",
+                    "icecandidate",
+                    "idleTimeout",
+                    "filtered",
+                    "spamDetected",
                 ],
             ))?,
         };
@@ -138,13 +401,20 @@ impl<'de> Visitor<'de> for ChatEventVisitor {
 
 #[cfg(test)]
 mod tests {
-    use serde_test::{assert_de_tokens, assert_de_tokens_error, Token};
+    use std::time::Duration;
+
+    use proptest::prelude::*;
+    use proptest::strategy::LazyJust;
+    use serde_test::{assert_de_tokens, assert_de_tokens_error, assert_ser_tokens, Token};
 
     use vec1::vec1;
 
     use crate::status::OmegleStatus;
     use crate::types::chat_server::ChatServer;
     use crate::types::check_server::CheckServer;
+    use crate::types::filter_match::Direction;
+    use crate::types::rtc::SdpType;
+    use crate::types::spam_score::SpamSignal;
 
     use super::*;
 
@@ -203,6 +473,23 @@ mod tests {
         )
     }
 
+    #[test]
+    fn can_deserialize_partner_college_event() {
+        // Response: [["partnerCollege", "mit.edu"]]
+        let expected_val = vec1![ChatEvent::PartnerCollege(String::from("mit.edu"))];
+        assert_de_tokens(
+            &expected_val,
+            &[
+                Token::Seq { len: Some(1) },
+                Token::Seq { len: Some(2) },
+                Token::BorrowedStr("partnerCollege"),
+                Token::BorrowedStr("mit.edu"),
+                Token::SeqEnd,
+                Token::SeqEnd,
+            ],
+        )
+    }
+
     #[test]
     fn can_deserialize_status_info_event() {
         // Response:        [["statusInfo", {"count": 45148, "antinudeservers":
@@ -236,7 +523,10 @@ mod tests {
                 CheckServer(1),
                 CheckServer(4),
                 CheckServer(2)
-            ]
+            ],
+            rtmfp: Some(String::from("rtmfp://p2p.rtmfp.net")),
+            spy_queue_time: Some(80.12030000686646),
+            spyee_queue_time: Some(229.33259999752045),
         })];
         assert_de_tokens(
             &expected_val,
@@ -255,12 +545,15 @@ mod tests {
                 Token::BorrowedStr("waw2.omegle.com"),
                 Token::SeqEnd,
                 Token::BorrowedStr("spyQueueTime"),
+                Token::Some,
                 Token::F64(80.12030000686646),
                 Token::BorrowedStr("rtmfp"),
+                Token::Some,
                 Token::BorrowedStr("rtmfp://p2p.rtmfp.net"),
                 Token::BorrowedStr("antinudepercent"),
                 Token::F32(1.0),
                 Token::BorrowedStr("spyeeQueueTime"),
+                Token::Some,
                 Token::F64(229.33259999752045),
                 Token::BorrowedStr("timestamp"),
                 Token::F64(1685331229.225212),
@@ -288,6 +581,355 @@ mod tests {
         )
     }
 
+    #[test]
+    fn can_deserialize_rtc_events() {
+        // Response: [["rtccall"], ["rtcpeerdescription", {"type": "offer", "sdp": "v=0"}],
+        //           ["icecandidate", {"candidate": "candidate:1", "sdpMid": "0", "sdpMLineIndex": 0}]]
+        let expected_val = vec1![
+            ChatEvent::RtcCall,
+            ChatEvent::RtcPeerDescription(RtcSessionDescription {
+                sdp_type: SdpType::Offer,
+                sdp: String::from("v=0"),
+            }),
+            ChatEvent::IceCandidate(IceCandidate {
+                candidate: String::from("candidate:1"),
+                sdp_mid: Some(String::from("0")),
+                sdp_m_line_index: Some(0),
+            }),
+        ];
+        assert_de_tokens(
+            &expected_val,
+            &[
+                Token::Seq { len: Some(3) },
+                Token::Seq { len: Some(1) },
+                Token::BorrowedStr("rtccall"),
+                Token::SeqEnd,
+                Token::Seq { len: Some(2) },
+                Token::BorrowedStr("rtcpeerdescription"),
+                Token::Map { len: Some(2) },
+                Token::BorrowedStr("type"),
+                Token::UnitVariant {
+                    name: "SdpType",
+                    variant: "offer",
+                },
+                Token::BorrowedStr("sdp"),
+                Token::BorrowedStr("v=0"),
+                Token::MapEnd,
+                Token::SeqEnd,
+                Token::Seq { len: Some(2) },
+                Token::BorrowedStr("icecandidate"),
+                Token::Map { len: Some(3) },
+                Token::BorrowedStr("candidate"),
+                Token::BorrowedStr("candidate:1"),
+                Token::BorrowedStr("sdpMid"),
+                Token::Some,
+                Token::BorrowedStr("0"),
+                Token::BorrowedStr("sdpMLineIndex"),
+                Token::Some,
+                Token::U16(0),
+                Token::MapEnd,
+                Token::SeqEnd,
+                Token::SeqEnd,
+            ],
+        )
+    }
+
+    #[test]
+    fn can_deserialize_spy_events() {
+        // Response: [["question", "Cats or dogs?"], ["spyTyping", "Stranger 1"],
+        //           ["spyMessage", "Stranger 1", "cats"], ["spyStoppedTyping", "Stranger 2"],
+        //           ["spyDisconnected", "Stranger 2"]]
+        let expected_val = vec1![
+            ChatEvent::Question(String::from("Cats or dogs?")),
+            ChatEvent::SpyTyping(String::from("Stranger 1")),
+            ChatEvent::SpyMessage {
+                stranger: String::from("Stranger 1"),
+                message: String::from("cats"),
+            },
+            ChatEvent::SpyStoppedTyping(String::from("Stranger 2")),
+            ChatEvent::SpyDisconnected(String::from("Stranger 2")),
+        ];
+        assert_de_tokens(
+            &expected_val,
+            &[
+                Token::Seq { len: Some(5) },
+                Token::Seq { len: Some(2) },
+                Token::BorrowedStr("question"),
+                Token::BorrowedStr("Cats or dogs?"),
+                Token::SeqEnd,
+                Token::Seq { len: Some(2) },
+                Token::BorrowedStr("spyTyping"),
+                Token::BorrowedStr("Stranger 1"),
+                Token::SeqEnd,
+                Token::Seq { len: Some(3) },
+                Token::BorrowedStr("spyMessage"),
+                Token::BorrowedStr("Stranger 1"),
+                Token::BorrowedStr("cats"),
+                Token::SeqEnd,
+                Token::Seq { len: Some(2) },
+                Token::BorrowedStr("spyStoppedTyping"),
+                Token::BorrowedStr("Stranger 2"),
+                Token::SeqEnd,
+                Token::Seq { len: Some(2) },
+                Token::BorrowedStr("spyDisconnected"),
+                Token::BorrowedStr("Stranger 2"),
+                Token::SeqEnd,
+                Token::SeqEnd,
+            ],
+        );
+        assert!(expected_val.last().ends_chat())
+    }
+
+    #[test]
+    fn serializes_spy_message_with_both_elements() {
+        assert_ser_tokens(
+            &ChatEvent::SpyMessage {
+                stranger: String::from("Stranger 2"),
+                message: String::from("dogs"),
+            },
+            &[
+                Token::Seq { len: Some(3) },
+                Token::Str("spyMessage"),
+                Token::Str("Stranger 2"),
+                Token::Str("dogs"),
+                Token::SeqEnd,
+            ],
+        )
+    }
+
+    #[test]
+    fn serializes_to_wire_format() {
+        assert_ser_tokens(
+            &vec![
+                ChatEvent::Message(String::from("hi")),
+                ChatEvent::StartedTyping,
+                ChatEvent::CommonLikes(vec1![String::from("books")]),
+            ],
+            &[
+                Token::Seq { len: Some(3) },
+                Token::Seq { len: Some(2) },
+                Token::Str("gotMessage"),
+                Token::Str("hi"),
+                Token::SeqEnd,
+                Token::Seq { len: Some(1) },
+                Token::Str("typing"),
+                Token::SeqEnd,
+                Token::Seq { len: Some(2) },
+                Token::Str("commonLikes"),
+                Token::Seq { len: Some(1) },
+                Token::Str("books"),
+                Token::SeqEnd,
+                Token::SeqEnd,
+                Token::SeqEnd,
+            ],
+        )
+    }
+
+    #[test]
+    fn serializes_to_omegle_json() {
+        let events = vec1![
+            ChatEvent::Message(String::from("hi")),
+            ChatEvent::StartedTyping
+        ];
+        assert_eq!(
+            serde_json::to_string(&events).unwrap(),
+            r#"[["gotMessage","hi"],["typing"]]"#
+        )
+    }
+
+    /// Queue times are multiples of 1/8 so they survive going through text exactly
+    fn queue_time() -> impl Strategy<Value = Option<f64>> {
+        proptest::option::of((0u32..1_000_000).prop_map(|eighths| f64::from(eighths) / 8.0))
+    }
+
+    fn status() -> impl Strategy<Value = OmegleStatus> {
+        (
+            any::<u64>(),
+            proptest::collection::vec(any::<u8>(), 1..5),
+            proptest::collection::vec(any::<u8>(), 1..5),
+            proptest::option::of(any::<String>()),
+            queue_time(),
+            queue_time(),
+        )
+            .prop_map(
+                |(count, servers, check_servers, rtmfp, spy_queue_time, spyee_queue_time)| {
+                    OmegleStatus {
+                        count,
+                        servers: Vec1::try_from_vec(
+                            servers.into_iter().map(ChatServer::from).collect(),
+                        )
+                        .unwrap(),
+                        antinudeservers: Vec1::try_from_vec(
+                            check_servers.into_iter().map(CheckServer).collect(),
+                        )
+                        .unwrap(),
+                        rtmfp,
+                        spy_queue_time,
+                        spyee_queue_time,
+                    }
+                },
+            )
+    }
+
+    fn sdp_type() -> impl Strategy<Value = SdpType> {
+        prop_oneof![
+            Just(SdpType::Offer),
+            Just(SdpType::Answer),
+            Just(SdpType::Pranswer),
+            Just(SdpType::Rollback),
+        ]
+    }
+
+    /// Every event omegle can send, [`ChatEvent`] isn't `Clone` so unit events are built lazily
+    fn wire_event() -> impl Strategy<Value = ChatEvent> {
+        prop_oneof![
+            LazyJust::new(|| ChatEvent::Waiting),
+            LazyJust::new(|| ChatEvent::Connected),
+            status().prop_map(ChatEvent::StatusInfo),
+            any::<u64>().prop_map(ChatEvent::Count),
+            proptest::collection::vec(any::<String>(), 1..4)
+                .prop_map(|likes| ChatEvent::CommonLikes(Vec1::try_from_vec(likes).unwrap())),
+            any::<String>().prop_map(ChatEvent::ServerMessage),
+            any::<String>().prop_map(ChatEvent::PartnerCollege),
+            any::<String>().prop_map(ChatEvent::IdentDigests),
+            any::<String>().prop_map(ChatEvent::Error),
+            LazyJust::new(|| ChatEvent::ConnectionDied),
+            LazyJust::new(|| ChatEvent::Banned),
+            LazyJust::new(|| ChatEvent::StartedTyping),
+            LazyJust::new(|| ChatEvent::StoppedTyping),
+            any::<String>().prop_map(ChatEvent::Message),
+            LazyJust::new(|| ChatEvent::Disconnected),
+            any::<String>().prop_map(ChatEvent::Question),
+            any::<String>().prop_map(ChatEvent::SpyTyping),
+            any::<String>().prop_map(ChatEvent::SpyStoppedTyping),
+            (any::<String>(), any::<String>())
+                .prop_map(|(stranger, message)| ChatEvent::SpyMessage { stranger, message }),
+            any::<String>().prop_map(ChatEvent::SpyDisconnected),
+            LazyJust::new(|| ChatEvent::RtcCall),
+            (sdp_type(), any::<String>()).prop_map(|(sdp_type, sdp)| {
+                ChatEvent::RtcPeerDescription(RtcSessionDescription { sdp_type, sdp })
+            }),
+            (
+                any::<String>(),
+                proptest::option::of(any::<String>()),
+                proptest::option::of(any::<u16>())
+            )
+                .prop_map(|(candidate, sdp_mid, sdp_m_line_index)| {
+                    ChatEvent::IceCandidate(IceCandidate {
+                        candidate,
+                        sdp_mid,
+                        sdp_m_line_index,
+                    })
+                }),
+        ]
+    }
+
+    fn spam_signal() -> impl Strategy<Value = SpamSignal> {
+        prop_oneof![
+            any::<u64>()
+                .prop_map(|millis| SpamSignal::FastFirstMessage(Duration::from_millis(millis))),
+            Just(SpamSignal::NeverTyped),
+            any::<String>().prop_map(SpamSignal::SpamPhrase),
+            Just(SpamSignal::Link),
+        ]
+    }
+
+    /// Events created by the session, scores are multiples of 1/8 like queue times
+    fn local_event() -> impl Strategy<Value = ChatEvent> {
+        prop_oneof![
+            any::<bool>().prop_map(|disconnected| ChatEvent::IdleTimeout { disconnected }),
+            (
+                any::<String>(),
+                prop_oneof![
+                    Just(FilterAction::Drop),
+                    Just(FilterAction::Redact),
+                    Just(FilterAction::Disconnect),
+                ],
+                prop_oneof![Just(Direction::Outgoing), Just(Direction::Incoming)],
+            )
+                .prop_map(|(rule, action, direction)| {
+                    ChatEvent::Filtered(FilterMatch {
+                        rule,
+                        action,
+                        direction,
+                    })
+                }),
+            (
+                (0u8..=8).prop_map(|eighths| f32::from(eighths) / 8.0),
+                proptest::collection::vec(spam_signal(), 0..4),
+                any::<bool>(),
+                any::<bool>(),
+            )
+                .prop_map(|(score, signals, is_spam, disconnected)| {
+                    ChatEvent::SpamDetected {
+                        score: SpamScore {
+                            score,
+                            signals,
+                            is_spam,
+                        },
+                        disconnected,
+                    }
+                }),
+        ]
+    }
+
+    proptest! {
+        #[test]
+        fn wire_events_round_trip(events in proptest::collection::vec(wire_event(), 1..8)) {
+            let events = Vec1::try_from_vec(events).unwrap();
+            let json = serde_json::to_string(&events).unwrap();
+            prop_assert_eq!(serde_json::from_str::<Vec1<ChatEvent>>(&json).unwrap(), events);
+        }
+
+        #[test]
+        fn reserializing_received_events_is_lossless(events in proptest::collection::vec(wire_event(), 1..8)) {
+            // What a proxy does: read omegle's response and send it on
+            let json = serde_json::to_string(&events).unwrap();
+            let received = serde_json::from_str::<Vec<ChatEvent>>(&json).unwrap();
+            prop_assert_eq!(serde_json::to_string(&received).unwrap(), json);
+        }
+
+        #[test]
+        fn local_events_round_trip(events in proptest::collection::vec(prop_oneof![wire_event(), local_event()], 1..8)) {
+            let events = Vec1::try_from_vec(events).unwrap();
+            let json = serde_json::to_string(&events).unwrap();
+            prop_assert_eq!(serde_json::from_str::<Vec1<ChatEvent>>(&json).unwrap(), events);
+        }
+    }
+
+    #[test]
+    fn deserializes_local_events() {
+        let events = vec![
+            ChatEvent::IdleTimeout { disconnected: true },
+            ChatEvent::Filtered(FilterMatch {
+                rule: String::from("links"),
+                action: FilterAction::Drop,
+                direction: Direction::Incoming,
+            }),
+            ChatEvent::SpamDetected {
+                score: SpamScore {
+                    score: 0.6,
+                    signals: vec![
+                        SpamSignal::FastFirstMessage(Duration::from_millis(300)),
+                        SpamSignal::NeverTyped,
+                    ],
+                    is_spam: true,
+                },
+                disconnected: false,
+            },
+        ];
+
+        let json = serde_json::to_string(&events).unwrap();
+        assert_eq!(
+            json,
+            r#"[["idleTimeout",true],["filtered",{"rule":"links","action":"Drop","direction":"Incoming"}],["spamDetected",{"score":0.6,"signals":[{"FastFirstMessage":{"secs":0,"nanos":300000000}},"NeverTyped"],"is_spam":true},false]]"#
+        );
+        assert_eq!(
+            serde_json::from_str::<Vec<ChatEvent>>(&json).unwrap(),
+            events
+        )
+    }
+
     #[test]
     fn can_not_deserialize_with_one_invalid_event() {
         // Response:     "[["connected"], ["commonLikes"],
@@ -349,7 +991,7 @@ mod tests {
                 // The rest are irrelevant but still want them here for completness/reference
                 // Token::SeqEnd,
             ],
-            "unknown variant `test`, expected one of `waiting`, `connected`, `statusInfo`, `count`, `commonLikes`, `serverMessage`, `identDigests`, `error`, `connectionDied`, `antinudeBanned`, `typing`, `stoppedTyping`, `gotMessage`, `strangerDisconnected`"
+            "unknown variant `test`, expected one of `waiting`, `connected`, `statusInfo`, `count`, `commonLikes`, `serverMessage`, `partnerCollege`, `identDigests`, `error`, `connectionDied`, `antinudeBanned`, `typing`, `stoppedTyping`, `gotMessage`, `strangerDisconnected`, `question`, `spyTyping`, `spyStoppedTyping`, `spyMessage`, `spyDisconnected`, `rtccall`, `rtcpeerdescription`, `icecandidate`, `idleTimeout`, `filtered`, `spamDetected`"
         )
     }
 }
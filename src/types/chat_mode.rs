@@ -0,0 +1,107 @@
+use strum::Display;
+
+use crate::types::college::College;
+
+/// Enum describing the section of omegle new chats are started in
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum ChatMode {
+    /// Regular text chat
+    #[default]
+    Text,
+    /// Video chat, signaling is done through the WebRTC events and the media is left up to the caller
+    Video,
+    /// The unmonitored video section
+    Unmonitored,
+    /// Spy mode, ask a question and watch two strangers discuss it
+    Spy { question: String },
+    /// Be one of the two strangers discussing a spy's question
+    Spyee,
+    /// Only talk to verified college students
+    College(College),
+}
+
+impl ChatMode {
+    /// Gets the extra `/start` query parameters needed to enter this mode
+    pub(crate) fn start_params(&self) -> Vec<(&'static str, String)> {
+        match self {
+            ChatMode::Text => vec![],
+            ChatMode::Video => vec![
+                ("camera", String::from("Webcam")),
+                ("webrtc", String::from("1")),
+            ],
+            ChatMode::Unmonitored => vec![
+                ("camera", String::from("Webcam")),
+                ("webrtc", String::from("1")),
+                ("group", String::from("unmon")),
+            ],
+            ChatMode::Spy { question } => vec![("ask", question.clone())],
+            ChatMode::Spyee => vec![("wantsspy", String::from("1"))],
+            ChatMode::College(college) => {
+                let mut params = vec![
+                    ("college", college.college.clone()),
+                    ("college_auth", college.auth.clone()),
+                ];
+                if college.any_college {
+                    params.push(("any_college", String::from("1")));
+                }
+                params
+            }
+        }
+    }
+}
+
+/// Enum describing the client capabilities advertised to omegle when starting a chat
+#[derive(Display, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Capability {
+    /// Able to solve recaptcha v2 challenges
+    #[strum(serialize = "recaptcha2")]
+    Recaptcha2,
+    /// Supports the third revision of the typing/events protocol
+    #[strum(serialize = "t3")]
+    T3,
+}
+
+impl Capability {
+    /// The capabilities sent by omegle's own web client
+    pub const DEFAULT: [Capability; 2] = [Capability::Recaptcha2, Capability::T3];
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text_mode_has_no_params() {
+        assert!(ChatMode::Text.start_params().is_empty())
+    }
+
+    #[test]
+    fn unmonitored_mode_enables_video() {
+        assert_eq!(
+            ChatMode::Unmonitored.start_params(),
+            vec![
+                ("camera", String::from("Webcam")),
+                ("webrtc", String::from("1")),
+                ("group", String::from("unmon")),
+            ]
+        )
+    }
+
+    #[test]
+    fn college_mode_only_sends_any_college_when_set() {
+        let college = College::new("mit.edu", "token", false);
+        assert_eq!(
+            ChatMode::College(college).start_params(),
+            vec![
+                ("college", String::from("mit.edu")),
+                ("college_auth", String::from("token")),
+            ]
+        )
+    }
+
+    #[test]
+    fn capabilities_serialize_properly() {
+        assert_eq!(Capability::Recaptcha2.to_string(), "recaptcha2");
+        assert_eq!(Capability::T3.to_string(), "t3");
+    }
+}
@@ -9,10 +9,17 @@ use serde::{
 /// advantage of the fact that they all follow the pattern of
 /// 'front' + number. It's essentially just a wrapper for [u8].
 #[derive(Debug, PartialEq, Clone, Copy)]
-pub(crate) struct ChatServer {
+pub struct ChatServer {
     pub(crate) id_number: u8,
 }
 
+impl ChatServer {
+    /// Gets the number of the server (Ex: 5 for front5)
+    pub fn get_id_number(&self) -> u8 {
+        self.id_number
+    }
+}
+
 impl From<u8> for ChatServer {
     fn from(value: u8) -> Self {
         Self { id_number: value }
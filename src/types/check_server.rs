@@ -2,12 +2,19 @@ use std::fmt;
 
 use serde::{
     de::{Error, Visitor},
-    Deserialize, Deserializer,
+    Deserialize, Deserializer, Serialize, Serializer,
 };
 
 /// Struct representing the Omegle verification servers
 #[derive(Debug, PartialEq, Clone, Copy)]
-pub(crate) struct CheckServer(pub(crate) u8);
+pub struct CheckServer(pub(crate) u8);
+
+impl CheckServer {
+    /// Gets the number of the server (Ex: 2 for waw2.omegle.com)
+    pub fn get_id_number(&self) -> u8 {
+        self.0
+    }
+}
 
 impl From<CheckServer> for String {
     fn from(value: CheckServer) -> Self {
@@ -15,6 +22,16 @@ impl From<CheckServer> for String {
     }
 }
 
+impl Serialize for CheckServer {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let server_string: String = (*self).into();
+        serializer.serialize_str(&server_string)
+    }
+}
+
 impl<'de> Deserialize<'de> for CheckServer {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -39,27 +56,24 @@ impl<'de> Visitor<'de> for CheckServerVisitor {
     where
         E: Error,
     {
-        if str.len() == 15 && str.starts_with("waw") && str.ends_with(".omegle.com") {
-            let variant_as_str = str.get(3..4).expect(
-                "str was checked to have at least one char between 'waw' and '.omegle.com'",
-            );
-            let variant: u8 = variant_as_str.parse().map_err(|_| {
-                E::custom(
-                    "expected check server string that starts with 'waw' to be followed by a u8",
-                )
-            })?;
-            Ok(CheckServer(variant))
-        } else {
-            Err(E::custom(
-                "expected check server string to start with 'waw' and end with '.omegle.com', and to be 15 chars long",
-            ))
-        }
+        let Some(variant_as_str) = str
+            .strip_prefix("waw")
+            .and_then(|rest| rest.strip_suffix(".omegle.com"))
+        else {
+            return Err(E::custom(
+                "expected check server string to start with 'waw' and end with '.omegle.com'",
+            ));
+        };
+        let variant: u8 = variant_as_str.parse().map_err(|_| {
+            E::custom("expected check server string that starts with 'waw' to be followed by a u8")
+        })?;
+        Ok(CheckServer(variant))
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use serde_test::{assert_de_tokens, assert_de_tokens_error, Token};
+    use serde_test::{assert_de_tokens, assert_de_tokens_error, assert_ser_tokens, Token};
 
     use super::CheckServer;
 
@@ -69,15 +83,33 @@ mod tests {
         assert_de_tokens(&expected_server, &[Token::BorrowedStr("waw1.omegle.com")])
     }
 
+    #[test]
+    fn serializes_to_full_domain() {
+        assert_ser_tokens(&CheckServer(4), &[Token::Str("waw4.omegle.com")])
+    }
+
+    #[test]
+    fn multi_digit_servers_round_trip() {
+        assert_ser_tokens(&CheckServer(12), &[Token::Str("waw12.omegle.com")]);
+        assert_de_tokens(
+            &CheckServer(255),
+            &[Token::BorrowedStr("waw255.omegle.com")],
+        )
+    }
+
     #[test]
     fn cant_deserialize_short_string() {
-        assert_de_tokens_error::<CheckServer>(&[Token::BorrowedStr("waw.omegle.com")], 
-        "expected check server string to start with 'waw' and end with '.omegle.com', and to be 15 chars long")
+        assert_de_tokens_error::<CheckServer>(
+            &[Token::BorrowedStr("waw.omegle.com")],
+            "expected check server string that starts with 'waw' to be followed by a u8",
+        )
     }
 
     #[test]
     fn cant_deserialize_incorrect_domain() {
-        assert_de_tokens_error::<CheckServer>(&[Token::BorrowedStr("www1.omegle.com")], 
-        "expected check server string to start with 'waw' and end with '.omegle.com', and to be 15 chars long")
+        assert_de_tokens_error::<CheckServer>(
+            &[Token::BorrowedStr("www1.omegle.com")],
+            "expected check server string to start with 'waw' and end with '.omegle.com'",
+        )
     }
 }
@@ -65,7 +65,7 @@ impl<'de> Deserialize<'de> for ClientID {
     }
 }
 
-struct ClientIDVisitor;
+pub(crate) struct ClientIDVisitor;
 
 impl<'de> Visitor<'de> for ClientIDVisitor {
     type Value = ClientID;
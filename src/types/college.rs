@@ -0,0 +1,60 @@
+/// Verified college credentials used to start chats in [`ChatMode::College`](crate::types::chat_mode::ChatMode::College)
+///
+/// The identifier and auth token are the ones omegle hands out after verifying
+/// a college email address.
+///
+/// # Examples
+/// Only talk to students from the same college:
+/// ```rust
+/// use omegle_rs::types::college::College;
+/// let college = College::new("mit.edu", "token", false);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct College {
+    pub(crate) college: String,
+    pub(crate) auth: String,
+    pub(crate) any_college: bool,
+}
+
+impl College {
+    /// Creates new college credentials
+    ///
+    /// If `any_college` is true, strangers from any verified college can be matched,
+    /// otherwise only strangers from the same college are
+    pub fn new<C: ToString, A: ToString>(college: C, auth: A, any_college: bool) -> Self {
+        Self {
+            college: college.to_string(),
+            auth: auth.to_string(),
+            any_college,
+        }
+    }
+
+    /// Gets the college identifier
+    pub fn get_college(&self) -> &str {
+        &self.college
+    }
+
+    /// Gets the auth token
+    pub fn get_auth(&self) -> &str {
+        &self.auth
+    }
+
+    /// Gets whether strangers from any college can be matched
+    pub fn is_any_college(&self) -> bool {
+        self.any_college
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_different_string_types() {
+        let auth = String::from("token");
+        let college = College::new("mit.edu", auth, true);
+        assert_eq!(college.get_college(), "mit.edu");
+        assert_eq!(college.get_auth(), "token");
+        assert!(college.is_any_college())
+    }
+}
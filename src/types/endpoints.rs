@@ -0,0 +1,152 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::{chat_server::ChatServer, check_server::CheckServer};
+
+/// Where the omegle servers are reached, useful for mirrors and local test servers
+///
+/// Chat servers are reached at `http://frontN.<domain>` and verification servers
+/// at `http://wawN.<domain>`, the status is fetched from the full status url.
+/// [Single host](Endpoints::single_host) endpoints reach every server at `http://<domain>`.
+///
+/// # Examples
+/// Talk to a mirror instead of omegle:
+/// ```rust
+/// use omegle_rs::types::endpoints::Endpoints;
+/// let endpoints = Endpoints::new()
+///     .status_url("https://mirror.example/status")
+///     .domain("mirror.example");
+/// ```
+///
+/// Talk to a [`Server`](crate::server::Server) running on this machine:
+/// ```rust
+/// use omegle_rs::types::endpoints::Endpoints;
+/// let endpoints = Endpoints::single_host("127.0.0.1", 8080);
+/// assert_eq!(endpoints.get_status_url(), "http://127.0.0.1:8080/status");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Endpoints {
+    status_url: String,
+    domain: String,
+    single_host: bool,
+}
+
+impl Default for Endpoints {
+    fn default() -> Self {
+        Self {
+            status_url: String::from(crate::status::OMEGLE_STATUS_URL),
+            domain: String::from("omegle.com"),
+            single_host: false,
+        }
+    }
+}
+
+impl Endpoints {
+    /// Creates new [`Endpoints`] pointing at omegle
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates new [`Endpoints`] reaching the status, chat and verification servers
+    /// at `host` on `port`, without the `frontN.` and `wawN.` subdomains
+    pub fn single_host<H: Display>(host: H, port: u16) -> Self {
+        let domain = format!("{host}:{port}");
+        Self {
+            status_url: format!("http://{domain}/status"),
+            domain,
+            single_host: true,
+        }
+    }
+
+    /// Sets the url the status is fetched from
+    pub fn status_url<U: ToString>(mut self, status_url: U) -> Self {
+        self.status_url = status_url.to_string();
+        self
+    }
+
+    /// Sets the domain the chat and verification servers are subdomains of
+    pub fn domain<D: ToString>(mut self, domain: D) -> Self {
+        self.domain = domain.to_string();
+        self
+    }
+
+    /// Gets the url the status is fetched from
+    pub fn get_status_url(&self) -> &str {
+        &self.status_url
+    }
+
+    /// Gets the domain the chat and verification servers are subdomains of
+    pub fn get_domain(&self) -> &str {
+        &self.domain
+    }
+
+    /// Gets whether every server is reached at the domain itself
+    pub fn is_single_host(&self) -> bool {
+        self.single_host
+    }
+
+    /// Builds the url of an endpoint of a chat server (Ex: `http://front1.omegle.com/send`)
+    pub(crate) fn chat_url(&self, server: ChatServer, endpoint: &str) -> String {
+        if self.single_host {
+            return format!("http://{}/{endpoint}", self.domain);
+        }
+        let chat_server_string = String::from(server);
+        format!("http://{chat_server_string}.{}/{endpoint}", self.domain)
+    }
+
+    /// Builds the url used to get a check code from a verification server
+    pub(crate) fn check_url(&self, server: CheckServer) -> String {
+        if self.single_host {
+            return format!("http://{}/check", self.domain);
+        }
+        format!("http://waw{}.{}/check", server.get_id_number(), self.domain)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_to_omegle() {
+        let endpoints = Endpoints::new();
+        assert_eq!(
+            endpoints.chat_url(ChatServer::from(2), "send"),
+            "http://front2.omegle.com/send"
+        );
+        assert_eq!(
+            endpoints.check_url(CheckServer(3)),
+            "http://waw3.omegle.com/check"
+        );
+        assert_eq!(endpoints.get_status_url(), "https://omegle.com/status")
+    }
+
+    #[test]
+    fn uses_custom_domain() {
+        let endpoints = Endpoints::new().domain("localhost:8080");
+        assert_eq!(
+            endpoints.chat_url(ChatServer::from(1), "events"),
+            "http://front1.localhost:8080/events"
+        );
+        assert_eq!(
+            endpoints.check_url(CheckServer(1)),
+            "http://waw1.localhost:8080/check"
+        )
+    }
+
+    #[test]
+    fn single_host_has_no_subdomains() {
+        let endpoints = Endpoints::single_host("127.0.0.1", 8080);
+        assert_eq!(
+            endpoints.chat_url(ChatServer::from(1), "events"),
+            "http://127.0.0.1:8080/events"
+        );
+        assert_eq!(
+            endpoints.check_url(CheckServer(2)),
+            "http://127.0.0.1:8080/check"
+        );
+        assert_eq!(endpoints.get_status_url(), "http://127.0.0.1:8080/status")
+    }
+}
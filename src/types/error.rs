@@ -5,10 +5,69 @@ pub enum OmegleLibError {
     #[error("invalid id: must not contain 'I', 'O', '1', '0'")]
     InvalidID,
 
+    /// Error returned when an interest is empty or too long
+    #[error(
+        "invalid interest '{0}': must not be empty or longer than {} chars",
+        crate::omegle::MAX_INTEREST_LENGTH
+    )]
+    InvalidInterest(String),
+
     /// Transparent error for reqwest
     #[error(transparent)]
     ReqwestError(#[from] reqwest::Error),
 
+    /// Transparent error for responses that could not be parsed
+    #[error(transparent)]
+    JsonError(#[from] serde_json::Error),
+
+    /// Error returned by a [`Transport`](crate::transport::Transport) that isn't backed by reqwest
+    #[error("transport failed: {0}")]
+    TransportError(String),
+
+    /// Error returned when a request took longer than the configured timeout
+    #[error("request timed out after {0:?}")]
+    Timeout(std::time::Duration),
+
+    /// Error returned when a [`SessionManager`](crate::session_manager::SessionManager) doesn't own the requested session
+    #[error("no session with id {0}")]
+    UnknownSession(crate::session_manager::SessionId),
+
+    /// Error returned when a [`FilterRule`](crate::filter::FilterRule) pattern is not a valid regular expression
+    #[error(transparent)]
+    InvalidFilterPattern(#[from] regex::Error),
+
+    /// Error returned when a message was not sent because of a [`FilterRule`](crate::filter::FilterRule)
+    #[error("message blocked by filter rule '{}'", .0.rule)]
+    Filtered(crate::types::filter_match::FilterMatch),
+
+    /// Transparent error for reading and writing files
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+
+    /// Error returned when a [`Config`](crate::config::Config) could not be parsed
+    #[cfg(feature = "config")]
+    #[error("invalid config: {0}")]
+    InvalidConfig(#[from] toml::de::Error),
+
+    /// Error returned when a [`Config`](crate::config::Config) could not be turned into TOML
+    #[cfg(feature = "config")]
+    #[error("could not write config: {0}")]
+    ConfigSerialization(#[from] toml::ser::Error),
+
+    /// Error returned when a [`Server`](crate::server::Server) could not bind or accept connections
+    #[cfg(feature = "server")]
+    #[error(transparent)]
+    ServerError(#[from] hyper::Error),
+
+    /// Error returned when a chat is started while cooling down from a ban, with the time left
+    #[error("banned by omegle, retry in {0:?}")]
+    Banned(std::time::Duration),
+
+    /// Error returned when every proxy of a [`ProxyPool`](crate::transport::ProxyPool) was removed
+    #[cfg(feature = "proxy")]
+    #[error("no healthy proxy left in the pool")]
+    NoHealthyProxy,
+
     /// An error response returned if a particular action failed during a chat session
     #[error("omegle server responded with '{0}'")]
     OmegleError(String),
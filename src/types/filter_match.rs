@@ -0,0 +1,34 @@
+use serde::{Deserialize, Serialize};
+
+/// Enum describing what happens to a message matched by a [`FilterRule`](crate::filter::FilterRule)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum FilterAction {
+    /// The message is not sent or not handed out
+    Drop,
+    /// The matched parts are replaced and the rest of the rules still apply
+    Redact,
+    /// The message is dropped and the chat disconnected
+    Disconnect,
+}
+
+/// Enum describing which messages a [`FilterRule`](crate::filter::FilterRule) applies to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Direction {
+    /// Messages we send
+    Outgoing,
+    /// Messages the stranger sends
+    Incoming,
+}
+
+/// A rule that matched a message, reported through
+/// [`ChatEvent::Filtered`](crate::types::chat_event::ChatEvent::Filtered) and
+/// [`OmegleLibError::Filtered`](crate::types::error::OmegleLibError::Filtered)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FilterMatch {
+    /// The name of the rule
+    pub rule: String,
+    /// What was done to the message
+    pub action: FilterAction,
+    /// Whether the message was sent or received
+    pub direction: Direction,
+}
@@ -1,18 +1,26 @@
-use strum::Display;
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumString};
 
 /// Enum for working with omegle language codes in Rust
-#[derive(Display, Debug, Clone, Copy)]
+///
+/// Can be parsed from the language code it serializes to (Ex: `"fr"`)
+#[derive(Display, EnumString, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum LangCode {
     #[strum(serialize = "en")]
+    #[serde(rename = "en")]
     English,
     #[strum(serialize = "fr")]
+    #[serde(rename = "fr")]
     French,
     #[strum(serialize = "es")]
+    #[serde(rename = "es")]
     Spanish,
 }
 
 #[cfg(test)]
 mod tests {
+    use serde_test::{assert_tokens, Token};
+
     use crate::types::lang::LangCode;
 
     #[test]
@@ -22,4 +30,21 @@ mod tests {
         assert_eq!(LangCode::French.to_string(), "fr");
         assert_eq!(LangCode::Spanish.to_string(), "es");
     }
+
+    #[test]
+    fn parses_from_code() {
+        assert_eq!("fr".parse::<LangCode>(), Ok(LangCode::French));
+        assert!("xx".parse::<LangCode>().is_err());
+    }
+
+    #[test]
+    fn serde_uses_the_code() {
+        assert_tokens(
+            &LangCode::French,
+            &[Token::UnitVariant {
+                name: "LangCode",
+                variant: "fr",
+            }],
+        )
+    }
 }
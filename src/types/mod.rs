@@ -1,7 +1,14 @@
 pub mod chat_event;
+pub mod chat_mode;
 pub mod chat_server;
 pub mod check_server;
 pub mod client_id;
+pub mod college;
+pub mod endpoints;
 pub mod error;
+pub mod filter_match;
 pub mod lang;
 pub mod rand_id;
+pub mod rtc;
+pub mod spam_score;
+pub mod start_response;
@@ -1,7 +1,7 @@
 use crate::types::error::OmegleLibError;
 use rand::distributions::Distribution;
 use rand::Rng;
-use serde::{Serialize, Serializer};
+use serde::{Deserialize, Serialize, Serializer};
 
 /// Omegle's Random ID used internally by Omegle to assign you with
 /// (relatively) new chatters each time. Meant to be used througout
@@ -25,7 +25,8 @@ use serde::{Serialize, Serializer};
 /// let id = "ABCDEFGH";
 /// let rand_id = RandID::try_from(id).expect("Follows convention");
 /// ```
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(try_from = "String")]
 pub struct RandID {
     // Better than storing in string since we know it must be 8 chars
     id: [char; 8],
@@ -40,7 +41,7 @@ impl TryFrom<String> for RandID {
         if value.len() == 8
             && value
                 .chars()
-                .all(|char| (char != '1' && char != '0' && char != 'I' && char != 'O'))
+                .all(|char| char != '1' && char != '0' && char != 'I' && char != 'O')
         {
             let mut id: [char; 8] = Default::default();
             for element in value.char_indices() {
@@ -63,7 +64,7 @@ impl TryFrom<&str> for RandID {
         if value.len() == 8
             && value
                 .chars()
-                .all(|char| (char != '1' && char != '0' && char != 'I' && char != 'O'))
+                .all(|char| char != '1' && char != '0' && char != 'I' && char != 'O')
         {
             let mut id: [char; 8] = Default::default();
             for element in value.char_indices() {
@@ -101,6 +102,12 @@ impl Serialize for RandID {
     }
 }
 
+impl Default for RandID {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl RandID {
     /// Creates a new [`RandID`] randomly using the convention
     pub fn new() -> Self {
@@ -137,7 +144,7 @@ impl Distribution<u8> for OmegleCharset {
 
 #[cfg(test)]
 mod tests {
-    use serde_test::{assert_ser_tokens, Token};
+    use serde_test::{assert_de_tokens, assert_de_tokens_error, assert_ser_tokens, Token};
 
     use super::*;
 
@@ -182,4 +189,18 @@ mod tests {
         let rand_id = RandID::try_from("ABCDEFGH").expect("Is valid id");
         assert_ser_tokens(&rand_id, &[Token::Str("ABCDEFGH")])
     }
+
+    #[test]
+    fn deserializes_valid_id() {
+        let rand_id = RandID::try_from("ABCDEFGH").expect("Is valid id");
+        assert_de_tokens(&rand_id, &[Token::Str("ABCDEFGH")])
+    }
+
+    #[test]
+    fn deserializing_invalid_id_errors() {
+        assert_de_tokens_error::<RandID>(
+            &[Token::Str("ABCDE1IO")],
+            "invalid id: must not contain 'I', 'O', '1', '0'",
+        )
+    }
 }
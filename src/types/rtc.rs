@@ -0,0 +1,94 @@
+use serde::{Deserialize, Serialize};
+
+/// The kind of session description being exchanged
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum SdpType {
+    Offer,
+    Answer,
+    Pranswer,
+    Rollback,
+}
+
+/// A WebRTC session description (SDP offer or answer) exchanged with the stranger
+///
+/// Follows the shape of the browser's `RTCSessionDescriptionInit`, which is what
+/// omegle forwards between the two parties
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct RtcSessionDescription {
+    #[serde(rename = "type")]
+    pub sdp_type: SdpType,
+    pub sdp: String,
+}
+
+/// A WebRTC ICE candidate exchanged with the stranger
+///
+/// Follows the shape of the browser's `RTCIceCandidateInit`
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct IceCandidate {
+    pub candidate: String,
+    #[serde(default)]
+    pub sdp_mid: Option<String>,
+    #[serde(default, rename = "sdpMLineIndex")]
+    pub sdp_m_line_index: Option<u16>,
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_test::{assert_tokens, Token};
+
+    use super::*;
+
+    #[test]
+    fn can_deserialize_and_serialize_session_description() {
+        let desc = RtcSessionDescription {
+            sdp_type: SdpType::Offer,
+            sdp: String::from("v=0"),
+        };
+        assert_tokens(
+            &desc,
+            &[
+                Token::Struct {
+                    name: "RtcSessionDescription",
+                    len: 2,
+                },
+                Token::Str("type"),
+                Token::UnitVariant {
+                    name: "SdpType",
+                    variant: "offer",
+                },
+                Token::Str("sdp"),
+                Token::Str("v=0"),
+                Token::StructEnd,
+            ],
+        )
+    }
+
+    #[test]
+    fn can_deserialize_and_serialize_ice_candidate() {
+        let candidate = IceCandidate {
+            candidate: String::from("candidate:1 1 udp 2122260223 10.0.0.1 53421 typ host"),
+            sdp_mid: Some(String::from("0")),
+            sdp_m_line_index: Some(0),
+        };
+        assert_tokens(
+            &candidate,
+            &[
+                Token::Struct {
+                    name: "IceCandidate",
+                    len: 3,
+                },
+                Token::Str("candidate"),
+                Token::Str("candidate:1 1 udp 2122260223 10.0.0.1 53421 typ host"),
+                Token::Str("sdpMid"),
+                Token::Some,
+                Token::Str("0"),
+                Token::Str("sdpMLineIndex"),
+                Token::Some,
+                Token::U16(0),
+                Token::StructEnd,
+            ],
+        )
+    }
+}
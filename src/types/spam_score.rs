@@ -0,0 +1,39 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// Enum describing the traits of a spam bot found in a [`Timeline`](crate::spam::Timeline)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SpamSignal {
+    /// The first message came sooner after connecting than a human could type it
+    FastFirstMessage(Duration),
+    /// The stranger sent messages without ever typing
+    NeverTyped,
+    /// A message contained a known spam phrase
+    SpamPhrase(String),
+    /// A message contained a link
+    Link,
+}
+
+impl SpamSignal {
+    /// How much the signal adds to the score
+    pub fn weight(&self) -> f32 {
+        match self {
+            SpamSignal::FastFirstMessage(_) => 0.35,
+            SpamSignal::NeverTyped => 0.25,
+            SpamSignal::SpamPhrase(_) => 0.4,
+            SpamSignal::Link => 0.3,
+        }
+    }
+}
+
+/// How likely a stranger is to be a spam bot
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpamScore {
+    /// Sum of the weights of the signals, capped at 1
+    pub score: f32,
+    /// Every signal found, in order
+    pub signals: Vec<SpamSignal>,
+    /// Whether the score reached the classifier's threshold
+    pub is_spam: bool,
+}
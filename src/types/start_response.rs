@@ -0,0 +1,190 @@
+use std::fmt;
+
+use serde::{
+    de::{Error, IgnoredAny, MapAccess, Visitor},
+    Deserialize, Deserializer,
+};
+
+use crate::status::OmegleStatus;
+use crate::types::chat_event::ChatEvent;
+use crate::types::client_id::{ClientID, ClientIDVisitor};
+
+/// Type describing the response to a `/start` request
+///
+/// Omegle responds with an object containing the client id and the first events
+/// of the chat (and sometimes the current status), but older servers respond
+/// with just the client id string, so both shapes are accepted.
+#[derive(Debug, PartialEq)]
+pub(crate) struct StartResponse {
+    pub(crate) client_id: ClientID,
+    pub(crate) events: Vec<ChatEvent>,
+    pub(crate) status_info: Option<OmegleStatus>,
+}
+
+impl StartResponse {
+    /// Gets the events that should be handed out before polling the server,
+    /// the status info (if any) comes first
+    pub(crate) fn into_initial_events(self) -> Vec<ChatEvent> {
+        self.status_info
+            .map(ChatEvent::StatusInfo)
+            .into_iter()
+            .chain(self.events)
+            .collect()
+    }
+}
+
+impl<'de> Deserialize<'de> for StartResponse {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(StartResponseVisitor)
+    }
+}
+
+struct StartResponseVisitor;
+
+impl<'de> Visitor<'de> for StartResponseVisitor {
+    type Value = StartResponse;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "A client id string or an object with a 'clientID' and optionally 'events' and 'statusInfo'"
+        )
+    }
+
+    fn visit_str<E>(self, str: &str) -> Result<StartResponse, E>
+    where
+        E: Error,
+    {
+        let client_id = ClientIDVisitor.visit_str(str)?;
+        Ok(StartResponse {
+            client_id,
+            events: Vec::new(),
+            status_info: None,
+        })
+    }
+
+    fn visit_map<M>(self, mut map: M) -> Result<StartResponse, M::Error>
+    where
+        M: MapAccess<'de>,
+    {
+        let mut client_id = None;
+        let mut events = Vec::new();
+        let mut status_info = None;
+
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "clientID" => client_id = Some(map.next_value::<ClientID>()?),
+                "events" => events = map.next_value::<Vec<ChatEvent>>()?,
+                "statusInfo" => status_info = Some(map.next_value::<OmegleStatus>()?),
+                _ => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+
+        let client_id = client_id.ok_or(Error::missing_field("clientID"))?;
+        Ok(StartResponse {
+            client_id,
+            events,
+            status_info,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_test::{assert_de_tokens, assert_de_tokens_error, Token};
+    use vec1::vec1;
+
+    use crate::types::chat_server::ChatServer;
+    use crate::types::check_server::CheckServer;
+    use crate::types::client_id::ServerType;
+
+    use super::*;
+
+    fn client_id() -> ClientID {
+        ClientID {
+            server_type: ServerType::Central,
+            server_id: 3,
+            user_id: ['a'; 30],
+        }
+    }
+
+    #[test]
+    fn can_deserialize_bare_client_id() {
+        let expected_val = StartResponse {
+            client_id: client_id(),
+            events: Vec::new(),
+            status_info: None,
+        };
+        assert_de_tokens(
+            &expected_val,
+            &[Token::Str("central3:aaaaaaaaaaaaaaaaaaaaaaaaaaaaaa")],
+        )
+    }
+
+    #[test]
+    fn can_deserialize_object_with_events() {
+        // Response: {"clientID": "central3:aaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+        //            "events": [["waiting"]], "ident": "..."}
+        let expected_val = StartResponse {
+            client_id: client_id(),
+            events: vec![ChatEvent::Waiting],
+            status_info: None,
+        };
+        assert_de_tokens(
+            &expected_val,
+            &[
+                Token::Map { len: Some(3) },
+                Token::Str("clientID"),
+                Token::Str("central3:aaaaaaaaaaaaaaaaaaaaaaaaaaaaaa"),
+                Token::Str("events"),
+                Token::Seq { len: Some(1) },
+                Token::Seq { len: Some(1) },
+                Token::BorrowedStr("waiting"),
+                Token::SeqEnd,
+                Token::SeqEnd,
+                Token::Str("ident"),
+                Token::Str("abcdef"),
+                Token::MapEnd,
+            ],
+        )
+    }
+
+    #[test]
+    fn can_not_deserialize_object_without_client_id() {
+        assert_de_tokens_error::<StartResponse>(
+            &[
+                Token::Map { len: Some(1) },
+                Token::Str("events"),
+                Token::Seq { len: Some(0) },
+                Token::SeqEnd,
+                Token::MapEnd,
+            ],
+            "missing field `clientID`",
+        )
+    }
+
+    #[test]
+    fn initial_events_start_with_status_info() {
+        let status = OmegleStatus {
+            count: 1,
+            servers: vec1![ChatServer { id_number: 1 }],
+            antinudeservers: vec1![CheckServer(1)],
+            rtmfp: None,
+            spy_queue_time: None,
+            spyee_queue_time: None,
+        };
+        let resp = StartResponse {
+            client_id: client_id(),
+            events: vec![ChatEvent::Connected],
+            status_info: Some(status),
+        };
+        let events = resp.into_initial_events();
+        assert!(matches!(events[0], ChatEvent::StatusInfo(_)));
+        assert_eq!(events[1], ChatEvent::Connected)
+    }
+}